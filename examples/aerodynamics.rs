@@ -0,0 +1,59 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+use rand::{thread_rng, Rng};
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			..default()
+		})
+		.add_plugin(DebugPlugin)
+		.insert_resource(Gravity(Vec2::Y * -300.0))
+		.add_startup_system(setup)
+		.add_system(respawn)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Updraft that lifts the plates that fall through it only with the air it moves
+	commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(200.0, -100.0, 0.0)))
+		.insert_bundle(ColliderBundle::new(ColliderShape::Square(60.0, 200.0)))
+		.insert(ForceZone::Wind { acceleration: Vec2::ZERO, velocity: vec2(0.0, 250.0) });
+
+	for i in 0..12 {
+		// Thin plates flutter while heavier blocks fall almost straight
+		let (shape, aerodynamics) = if i % 3 == 0 {
+			(ColliderShape::Square(15.0, 15.0), Aerodynamics::plate(0.0002))
+		} else {
+			(ColliderShape::Square(30.0, 2.0), Aerodynamics::plate(0.0005))
+		};
+		commands.spawn_bundle(TransformBundle::from_transform(
+			Transform::from_xyz(-350.0 + i as f32 * 60.0, 250.0, 0.0)
+				.with_rotation(Quat::from_rotation_z(thread_rng().gen_range(-0.5..0.5))),
+		))
+		.insert_bundle(KinematicBundle {
+			forces: ForcesBundle {
+				resistance: Resistance::from_shape(1.0, &shape),
+				..default()
+			},
+			..default()
+		})
+		.insert_bundle(ColliderBundle::new(shape))
+		.insert(aerodynamics);
+	}
+}
+
+/// Moves the bodies that leave the screen back to the top
+fn respawn(mut query: Query<(&mut Transform, &mut Velocity), With<Aerodynamics>>) {
+	for (mut transform, mut velocity) in query.iter_mut() {
+		if transform.translation.y < -300.0 || transform.translation.y > 350.0 || transform.translation.x.abs() > 500.0 {
+			transform.translation = vec2(thread_rng().gen_range(-350.0..350.0), 250.0).extend(0.0);
+			velocity.linear = Vec2::ZERO;
+			velocity.angular = 0.0;
+		}
+	}
+}
@@ -0,0 +1,110 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+const LAUNCH_SPEED: f32 = 450.0;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			..default()
+		})
+		.add_plugin(DebugPlugin)
+		.insert_resource(Gravity(Vec2::Y * -300.0))
+		.insert_resource(Turret { timer: Timer::from_seconds(0.8, true), high: false })
+		.add_startup_system(setup)
+		.add_system(fire)
+		.add_system(patrol)
+		.add_system(despawn)
+		.run();
+}
+
+/// Alternates between the low and the high arc
+struct Turret {
+	timer: Timer,
+	high: bool,
+}
+
+#[derive(Component)]
+struct Target;
+
+#[derive(Component)]
+struct Shell;
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Floating target that patrols without falling
+	let shape = ColliderShape::Square(40.0, 40.0);
+	commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(100.0, 0.0, 0.0)))
+		.insert_bundle(RigidBodyBundle {
+			kinematic: KinematicBundle {
+				dynamic: DynamicBundle {
+					velocity: Velocity { linear: vec2(120.0, 0.0), ..default() },
+					..default()
+				},
+				forces: ForcesBundle {
+					resistance: Resistance::from_shape(100.0, &shape),
+					..default()
+				},
+			},
+			collider: ColliderBundle::new(shape),
+			..default()
+		})
+		.insert(GravityScale(0.0))
+		.insert(LockedAxes { translation_x: false, translation_y: true, rotation: true })
+		.insert(Target);
+}
+
+/// Bounces the target between the sides of the screen
+fn patrol(mut query: Query<(&Transform, &mut Velocity), With<Target>>) {
+	for (transform, mut velocity) in query.iter_mut() {
+		let x = transform.translation.x;
+		if (x > 350.0 && velocity.linear.x > 0.0) || (x < -100.0 && velocity.linear.x < 0.0) {
+			velocity.linear.x = -velocity.linear.x;
+		}
+	}
+}
+
+fn fire(
+	mut commands: Commands,
+	time: Res<Time>,
+	gravity: Res<Gravity>,
+	mut turret: ResMut<Turret>,
+	target: Query<(&Transform, &Velocity), With<Target>>,
+) {
+	if !turret.timer.tick(time.delta()).just_finished() { return; }
+	let (transform, velocity) = target.single();
+	let from = vec2(-350.0, -250.0);
+	let Some(arcs) = gravity.intercept(from, transform.translation.truncate(), velocity.linear, LAUNCH_SPEED, 5.0) else { return };
+	let arc = if turret.high { arcs.high } else { arcs.low };
+	turret.high = !turret.high;
+
+	let shape = ColliderShape::Circle(6.0);
+	commands.spawn_bundle(TransformBundle::from_transform(Transform::from_translation(from.extend(0.0))))
+		.insert_bundle(RigidBodyBundle {
+			kinematic: KinematicBundle {
+				dynamic: DynamicBundle {
+					velocity: Velocity { linear: arc.velocity, ..default() },
+					..default()
+				},
+				forces: ForcesBundle {
+					resistance: Resistance::from_shape(1.0, &shape),
+					..default()
+				},
+			},
+			collider: ColliderBundle::new(shape),
+			..default()
+		})
+		.insert(Shell);
+}
+
+/// Removes the shells that fall out of the screen
+fn despawn(mut commands: Commands, query: Query<(Entity, &Transform), With<Shell>>) {
+	for (entity, transform) in query.iter() {
+		if transform.translation.y < -400.0 {
+			commands.entity(entity).despawn();
+		}
+	}
+}
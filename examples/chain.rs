@@ -0,0 +1,66 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			substeps: 4,
+			// Long chains need more iterations to not stretch
+			joint_iterations: 16,
+			..default()
+		})
+		.add_plugin(DebugPlugin)
+		// Grab the bodies with the left mouse button
+		.add_plugin(MouseDragPlugin::default())
+		.insert_resource(Gravity(Vec2::Y * -500.0))
+		.add_startup_system(setup)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Bridge of planks between two points, a bit longer than the gap so it sags
+	Chain::new(ChainAnchor::Point(vec2(-250.0, 0.0)), ChainAnchor::Point(vec2(50.0, 0.0)), 8)
+		.with_segment_length(40.0)
+		.with_collider(ColliderShape::Square(20.0, 5.0))
+		.spawn(&mut commands);
+
+	// Box that falls on the bridge
+	let shape = ColliderShape::Square(20.0, 20.0);
+	commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(-100.0, 200.0, 0.0)))
+		.insert_bundle(RigidBodyBundle {
+			kinematic: KinematicBundle {
+				forces: ForcesBundle {
+					resistance: Resistance::from_shape(2.0, &shape),
+					..default()
+				},
+				..default()
+			},
+			collider: ColliderBundle::new(shape),
+			..default()
+		});
+
+	// Rope holding a weight that starts swinging
+	let weight_shape = ColliderShape::Circle(15.0);
+	let weight = commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(300.0, 100.0, 0.0)))
+		.insert_bundle(RigidBodyBundle {
+			kinematic: KinematicBundle {
+				forces: ForcesBundle {
+					resistance: Resistance::from_shape(2.0, &weight_shape),
+					..default()
+				},
+				..default()
+			},
+			collider: ColliderBundle::new(weight_shape),
+			..default()
+		})
+		.id();
+	Chain::new(ChainAnchor::Point(vec2(150.0, 100.0)), ChainAnchor::Entity(weight, Vec2::ZERO), 15)
+		.with_link(ChainLink::Rope)
+		.with_segment_mass(0.1)
+		.with_collider(ColliderShape::Circle(3.0))
+		.spawn(&mut commands);
+}
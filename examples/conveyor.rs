@@ -0,0 +1,70 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+use rand::{thread_rng, Rng};
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			substeps: 4,
+			..default()
+		})
+		.add_plugin(DebugPlugin)
+		.insert_resource(Gravity(Vec2::Y * -500.0))
+		.add_startup_system(setup)
+		.add_system(respawn)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Belts that zigzag down, a negative speed moves the top to the right. The last one is tilted
+	// like an escalator and carries the boxes back up
+	let belts = [
+		(vec2(-100.0, 150.0), 0.0, -120.0),
+		(vec2(100.0, 20.0), 0.0, 120.0),
+		(vec2(-100.0, -110.0), 0.0, -120.0),
+		(vec2(200.0, -180.0), 0.4, -150.0),
+	];
+	for (position, angle, speed) in belts {
+		commands.spawn_bundle(TransformBundle::from_transform(
+			Transform::from_translation(position.extend(0.0)).with_rotation(Quat::from_rotation_z(angle)),
+		))
+		.insert_bundle(RigidBodyBundle {
+			collider: ColliderBundle::new(ColliderShape::Square(200.0, 8.0)),
+			body: Body::Static,
+			..default()
+		})
+		.insert(SurfaceVelocity(speed));
+	}
+
+	for i in 0..8 {
+		let shape = ColliderShape::Square(12.0, 12.0);
+		commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(-250.0 + i as f32 * 40.0, 250.0, 0.0)))
+			.insert_bundle(RigidBodyBundle {
+				kinematic: KinematicBundle {
+					forces: ForcesBundle {
+						resistance: Resistance::from_shape(1.0, &shape),
+						..default()
+					},
+					..default()
+				},
+				collider: ColliderBundle::new(shape),
+				..default()
+			});
+	}
+}
+
+/// Moves the boxes that fall out of the screen back to the top
+fn respawn(mut query: Query<(&mut Transform, &mut Velocity, &Body)>) {
+	for (mut transform, mut velocity, body) in query.iter_mut() {
+		if *body == Body::Static { continue; }
+		if transform.translation.y < -300.0 || transform.translation.x.abs() > 450.0 {
+			transform.translation = vec2(thread_rng().gen_range(-280.0..0.0), 250.0).extend(0.0);
+			velocity.linear = Vec2::ZERO;
+			velocity.angular = 0.0;
+		}
+	}
+}
@@ -27,6 +27,7 @@ fn setup(mut commands: Commands) {
 			drag: Drag {
 				linear: 0.01,
 				angular: 0.01,
+				..default()
 			},
 			..default()
 		},
@@ -0,0 +1,61 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+const BOX_SIZE: f32 = 30.0;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			substeps: 4,
+			..default()
+		})
+		.add_plugin(DebugPlugin)
+		.insert_resource(Gravity(Vec2::Y * -500.0))
+		.add_startup_system(setup)
+		.add_system(explode)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Ground and a wall that shields the stack on the right
+	for (position, shape) in [
+		(vec2(0.0, -250.0), ColliderShape::Square(400.0, 20.0)),
+		(vec2(100.0, -150.0), ColliderShape::Square(10.0, 80.0)),
+	] {
+		commands.spawn_bundle(TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))))
+			.insert_bundle(RigidBodyBundle {
+				collider: ColliderBundle::new(shape),
+				body: Body::Static,
+				..default()
+			});
+	}
+
+	for x in [-150.0, 200.0] {
+		for i in 0..6 {
+			let shape = ColliderShape::Square(BOX_SIZE / 2.0, BOX_SIZE / 2.0);
+			commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(x, -215.0 + i as f32 * (BOX_SIZE + 2.0), 0.0)))
+				.insert_bundle(RigidBodyBundle {
+					kinematic: KinematicBundle {
+						forces: ForcesBundle {
+							resistance: Resistance::from_shape(1.0, &shape),
+							..default()
+						},
+						..default()
+					},
+					collider: ColliderBundle::new(shape),
+					..default()
+				});
+		}
+	}
+}
+
+/// Space blows up the point between the stacks, only the stack on the left is in sight
+fn explode(keyboard: Res<Input<KeyCode>>, mut explosions: Explosions) {
+	if keyboard.just_pressed(KeyCode::Space) {
+		explosions.explode_occluded(vec2(0.0, -200.0), 300.0, 400.0, Falloff::Linear, |_| true);
+	}
+}
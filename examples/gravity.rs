@@ -6,9 +6,10 @@ fn main() {
 	App::new()
 		.add_plugins(DefaultPlugins)
 		.add_plugin(PhysicsPlugin {
-			gravity_effect: GravityEffect::Velocity,
+			gravity_effect: GravityEffect::Acceleration,
+			..default()
 		})
-		.insert_resource(Gravity(Vec2::Y * -9.8))
+		.insert_resource(Gravity(Vec2::Y * -100.0))
 		.add_startup_system(setup)
 		.add_system(input)
 		.run();
@@ -39,7 +40,10 @@ fn input(
 	if keyboard.pressed(KeyCode::S) { delta.y -= 1. }
 	if keyboard.pressed(KeyCode::D) { delta.x += 1. }
 	if keyboard.pressed(KeyCode::A) { delta.x -= 1. }
-	velocity.linear = delta.normalize_or_zero() * 100.;
+	// The gravity keeps accelerating the square while no key is pressed
+	if delta != Vec2::ZERO {
+		velocity.linear = delta.normalize() * 100.;
+	}
 
 	if keyboard.just_pressed(KeyCode::R) {
 		velocity.linear = Vec2::ZERO;
@@ -0,0 +1,115 @@
+use std::f32::consts::{PI, FRAC_PI_2};
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			substeps: 4,
+			..default()
+		})
+		.insert_resource(Gravity(Vec2::Y * -500.0))
+		.add_startup_system(setup)
+		.add_system(log_broken_joints)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Pendulum, the pivot has no velocity so the joint can not move it
+	let pivot = spawn_square(&mut commands, vec2(-150.0, 150.0), Color::GRAY);
+	let bob = spawn_square(&mut commands, vec2(0.0, 150.0), Color::CYAN);
+	commands.entity(bob).insert_bundle(KinematicBundle::default());
+	commands.spawn().insert(Joint::new(pivot, bob, JointKind::Distance { min: 150.0, max: 150.0 }));
+
+	// Spring hanging from the ceiling, attached to a corner of the box so it also rotates
+	let ceiling = spawn_square(&mut commands, vec2(150.0, 150.0), Color::GRAY);
+	let weight = spawn_square(&mut commands, vec2(150.0, 50.0), Color::ORANGE);
+	commands.entity(weight).insert_bundle(KinematicBundle {
+		forces: ForcesBundle {
+			resistance: Resistance::from_shape(1.0, &ColliderShape::Square(10.0, 10.0)),
+			..default()
+		},
+		..default()
+	});
+	commands.spawn().insert(
+		Joint::new(ceiling, weight, JointKind::Spring { rest_length: 100.0, stiffness: 20.0, damping: 0.5 })
+			.with_anchors(Vec2::ZERO, vec2(-10.0, 10.0))
+	);
+
+	// Door hinged by its left edge that can only open 90 degrees
+	let frame = spawn_square(&mut commands, vec2(-150.0, -150.0), Color::GRAY);
+	let door = spawn_bar(&mut commands, vec2(-90.0, -150.0), Color::CYAN);
+	commands.spawn().insert(
+		Joint::new(frame, door, JointKind::Revolute { limits: Some((-FRAC_PI_2, 0.0)), motor: None })
+			.with_anchors(Vec2::ZERO, vec2(-60.0, 0.0))
+	);
+
+	// Wheel spinning with a motor
+	let axle = spawn_square(&mut commands, vec2(150.0, -150.0), Color::GRAY);
+	let wheel = spawn_bar(&mut commands, vec2(150.0, -150.0), Color::ORANGE);
+	commands.spawn().insert(Joint::new(axle, wheel, JointKind::Revolute {
+		limits: None,
+		motor: Some(JointMotor { target_velocity: PI, max_force: 2000.0 }),
+	}));
+
+	// Elevator that goes up 200 units and stops
+	let shaft = spawn_square(&mut commands, vec2(0.0, -250.0), Color::GRAY);
+	let elevator = spawn_bar(&mut commands, vec2(0.0, -250.0), Color::GREEN);
+	commands.spawn().insert(Joint::new(shaft, elevator, JointKind::Prismatic {
+		axis: Vec2::Y,
+		limits: Some((0.0, 200.0)),
+		motor: Some(JointMotor { target_velocity: 50.0, max_force: 5000.0 }),
+	}));
+
+	// Shelf welded to a wall that snaps when its own weight bends it too much
+	let wall = spawn_square(&mut commands, vec2(-300.0, 0.0), Color::GRAY);
+	let shelf = spawn_bar(&mut commands, vec2(-240.0, 0.0), Color::RED);
+	commands.spawn()
+		.insert(
+			Joint::new(wall, shelf, JointKind::Weld { linear: None, angular: None })
+				.with_anchors(Vec2::ZERO, vec2(-60.0, 0.0))
+		)
+		.insert(BreakTorque(20000.0));
+}
+
+fn log_broken_joints(mut events: EventReader<JointBroken>) {
+	for event in events.iter() {
+		info!("Joint between {:?} and {:?} broke", event.entity_a, event.entity_b);
+	}
+}
+
+fn spawn_bar(commands: &mut Commands, position: Vec2, color: Color) -> Entity {
+	let shape = ColliderShape::Square(60.0, 5.0);
+	commands.spawn_bundle(SpriteBundle {
+		sprite: Sprite {
+			custom_size: Some(vec2(120.0, 10.0)),
+			color,
+			..Default::default()
+		},
+		transform: Transform::from_translation(position.extend(0.0)),
+		..Default::default()
+	})
+	.insert_bundle(KinematicBundle {
+		forces: ForcesBundle {
+			resistance: Resistance::from_shape(1.0, &shape),
+			..default()
+		},
+		..default()
+	}).id()
+}
+
+fn spawn_square(commands: &mut Commands, position: Vec2, color: Color) -> Entity {
+	commands.spawn_bundle(SpriteBundle {
+		sprite: Sprite {
+			custom_size: Some(Vec2::splat(20.0)),
+			color,
+			..Default::default()
+		},
+		transform: Transform::from_translation(position.extend(0.0)),
+		..Default::default()
+	}).id()
+}
@@ -0,0 +1,62 @@
+use std::f32::consts::TAU;
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+use rand::{thread_rng, Rng};
+
+const G: f32 = 100.0;
+const CORE_MASS: f32 = 5000.0;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			substeps: 2,
+			..default()
+		})
+		.insert_resource(NBodyGravity {
+			g: G,
+			softening: 5.0,
+			theta: 0.7,
+		})
+		.add_plugin(NBodyPlugin)
+		.add_startup_system(setup)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	spawn_star(&mut commands, Vec2::ZERO, Vec2::ZERO, CORE_MASS, 20.0, Color::YELLOW);
+
+	// Disk of small stars in roughly circular orbits around the core
+	let mut rng = thread_rng();
+	for _ in 0..500 {
+		let angle = rng.gen_range(0.0..TAU);
+		let radius = rng.gen_range(60.0..300.0);
+		let position = vec2(angle.cos(), angle.sin()) * radius;
+		let speed = (G * CORE_MASS / radius).sqrt();
+		spawn_star(&mut commands, position, position.perp().normalize() * speed, 1.0, 3.0, Color::WHITE);
+	}
+}
+
+fn spawn_star(commands: &mut Commands, position: Vec2, velocity: Vec2, mass: f32, size: f32, color: Color) {
+	commands.spawn_bundle(SpriteBundle {
+		sprite: Sprite {
+			custom_size: Some(Vec2::splat(size)),
+			color,
+			..Default::default()
+		},
+		transform: Transform::from_translation(position.extend(0.0)),
+		..Default::default()
+	})
+	.insert_bundle(KinematicBundle {
+		dynamic: DynamicBundle {
+			velocity: Velocity { linear: velocity, ..default() },
+			..default()
+		},
+		forces: ForcesBundle {
+			resistance: Resistance { mass, ..default() },
+			..default()
+		},
+	});
+}
@@ -0,0 +1,76 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+const PLANET_STRENGTH: f32 = 5_000_000.0;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			// Evaluates the pull of the planet twice per step so the orbits keep their energy
+			integrator: Integrator::VelocityVerlet,
+			substeps: 4,
+			..default()
+		})
+		.add_startup_system(setup)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Planet that pulls everything
+	commands.spawn_bundle(SpriteBundle {
+		sprite: Sprite {
+			custom_size: Some(Vec2::splat(60.0)),
+			color: Color::ORANGE,
+			..Default::default()
+		},
+		..Default::default()
+	})
+	.insert(Attractor {
+		strength: PLANET_STRENGTH,
+		radius: f32::INFINITY,
+		falloff: Falloff::InverseSquare,
+	});
+
+	// Moons in circular orbits, v = sqrt(s / r)
+	for (radius, color) in [(120.0, Color::CYAN), (200.0, Color::GREEN), (280.0, Color::PINK)] {
+		spawn_moon(&mut commands, vec2(radius, 0.0), vec2(0.0, (PLANET_STRENGTH / radius).sqrt()), color);
+	}
+
+	// Repulsor that bends the orbit of the moons that get close
+	commands.spawn_bundle(SpriteBundle {
+		sprite: Sprite {
+			custom_size: Some(Vec2::splat(20.0)),
+			color: Color::RED,
+			..Default::default()
+		},
+		transform: Transform::from_xyz(-320.0, 0.0, 0.0),
+		..Default::default()
+	})
+	.insert(Attractor {
+		strength: -2000.0,
+		radius: 100.0,
+		falloff: Falloff::Linear,
+	});
+}
+
+fn spawn_moon(commands: &mut Commands, position: Vec2, velocity: Vec2, color: Color) {
+	commands.spawn_bundle(SpriteBundle {
+		sprite: Sprite {
+			custom_size: Some(Vec2::splat(15.0)),
+			color,
+			..Default::default()
+		},
+		transform: Transform::from_translation(position.extend(0.0)),
+		..Default::default()
+	})
+	.insert_bundle(KinematicBundle {
+		dynamic: DynamicBundle {
+			velocity: Velocity { linear: velocity, ..default() },
+			..default()
+		},
+		..default()
+	});
+}
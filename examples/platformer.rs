@@ -0,0 +1,80 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+const SPEED: f32 = 200.0;
+const JUMP_SPEED: f32 = 450.0;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			..default()
+		})
+		.add_plugin(DebugPlugin)
+		.insert_resource(Gravity(Vec2::Y * -800.0))
+		.add_startup_system(setup)
+		.add_system(control)
+		.run();
+}
+
+#[derive(Component)]
+struct Player;
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Solid ground and platforms that can be jumped onto from below
+	commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(0.0, -250.0, 0.0)))
+		.insert_bundle(RigidBodyBundle {
+			collider: ColliderBundle::new(ColliderShape::Square(400.0, 20.0)),
+			body: Body::Static,
+			..default()
+		});
+	for position in [vec2(-150.0, -130.0), vec2(100.0, -30.0), vec2(-100.0, 70.0)] {
+		commands.spawn_bundle(TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))))
+			.insert_bundle(RigidBodyBundle {
+				collider: ColliderBundle::new(ColliderShape::Square(100.0, 5.0)),
+				body: Body::Static,
+				..default()
+			})
+			.insert(OneWay::default());
+	}
+
+	let shape = ColliderShape::Square(12.0, 20.0);
+	commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(0.0, -200.0, 0.0)))
+		.insert_bundle(RigidBodyBundle {
+			kinematic: KinematicBundle {
+				forces: ForcesBundle {
+					resistance: Resistance::from_shape(1.0, &shape),
+					..default()
+				},
+				..default()
+			},
+			collider: ColliderBundle::new(shape),
+			material: PhysicsMaterial { friction: 0.0, ..default() },
+			..default()
+		})
+		.insert(LockedAxes::ROTATION)
+		.insert(PassThrough::default())
+		.insert(Player);
+}
+
+/// a/d move, w jumps and s drops from the platform below
+fn control(
+	keyboard: Res<Input<KeyCode>>,
+	mut query: Query<(&mut Velocity, &mut PassThrough, &CollisionInfo), With<Player>>,
+) {
+	for (mut velocity, mut pass, info) in query.iter_mut() {
+		let direction = keyboard.pressed(KeyCode::D) as i32 - keyboard.pressed(KeyCode::A) as i32;
+		velocity.linear.x = direction as f32 * SPEED;
+
+		let grounded = info.is_colliding && velocity.linear.y.abs() < 1.0;
+		if grounded && keyboard.just_pressed(KeyCode::W) {
+			velocity.linear.y = JUMP_SPEED;
+		}
+		if keyboard.just_pressed(KeyCode::S) {
+			**pass = 0.2;
+		}
+	}
+}
@@ -0,0 +1,59 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+const MAIN_ENGINE: usize = 0;
+const TURN_LEFT: [usize; 2] = [1, 2];
+const TURN_RIGHT: [usize; 2] = [3, 4];
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin::default())
+		.add_plugin(DebugPlugin)
+		.add_startup_system(setup)
+		.add_system(pilot)
+		.add_system(wrap)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	let shape = ColliderShape::Polygon(vec![vec2(0.0, 20.0), vec2(-12.0, -12.0), vec2(12.0, -12.0)]);
+	commands.spawn_bundle(TransformBundle::default())
+		.insert_bundle(KinematicBundle {
+			forces: ForcesBundle {
+				resistance: Resistance::from_shape(1.0, &shape),
+				..default()
+			},
+			..default()
+		})
+		.insert_bundle(ColliderBundle::new(shape))
+		// Main engine at the back and pairs of small side thrusters that only turn the ship
+		.insert(Thrusters(vec![
+			Thruster::new(vec2(0.0, -12.0), Vec2::Y, 200.0),
+			Thruster::new(vec2(10.0, 10.0), -Vec2::X, 10.0),
+			Thruster::new(vec2(-10.0, -10.0), Vec2::X, 10.0),
+			Thruster::new(vec2(-10.0, 10.0), Vec2::X, 10.0),
+			Thruster::new(vec2(10.0, -10.0), -Vec2::X, 10.0),
+		]));
+}
+
+/// w fires the main engine, a and d turn the ship
+fn pilot(keyboard: Res<Input<KeyCode>>, mut query: Query<&mut Thrusters>) {
+	let throttle = |key| if keyboard.pressed(key) { 1.0 } else { 0.0 };
+	for mut thrusters in query.iter_mut() {
+		thrusters[MAIN_ENGINE].throttle = throttle(KeyCode::W);
+		for i in TURN_LEFT { thrusters[i].throttle = throttle(KeyCode::A); }
+		for i in TURN_RIGHT { thrusters[i].throttle = throttle(KeyCode::D); }
+	}
+}
+
+/// The ship that leaves the screen comes back from the other side
+fn wrap(mut query: Query<&mut Transform, With<Thrusters>>) {
+	for mut transform in query.iter_mut() {
+		let position = &mut transform.translation;
+		if position.x.abs() > 400.0 { position.x = -position.x.signum() * 400.0; }
+		if position.y.abs() > 300.0 { position.y = -position.y.signum() * 300.0; }
+	}
+}
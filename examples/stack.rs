@@ -0,0 +1,62 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+const BOX_SIZE: f32 = 40.0;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			// More substeps keep tall stacks stable
+			substeps: 8,
+			..default()
+		})
+		.insert_resource(Gravity(Vec2::Y * -500.0))
+		.add_startup_system(setup)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Ground
+	commands.spawn_bundle(SpriteBundle {
+		sprite: Sprite {
+			custom_size: Some(vec2(600.0, 40.0)),
+			color: Color::DARK_GRAY,
+			..Default::default()
+		},
+		transform: Transform::from_xyz(0.0, -250.0, 0.0),
+		..Default::default()
+	})
+	.insert_bundle(RigidBodyBundle {
+		collider: ColliderBundle::new(ColliderShape::Square(300.0, 20.0)),
+		body: Body::Static,
+		..default()
+	});
+
+	for i in 0..10 {
+		let shape = ColliderShape::Square(BOX_SIZE / 2.0, BOX_SIZE / 2.0);
+		commands.spawn_bundle(SpriteBundle {
+			sprite: Sprite {
+				custom_size: Some(Vec2::splat(BOX_SIZE)),
+				color: Color::ORANGE,
+				..Default::default()
+			},
+			transform: Transform::from_xyz(0.0, -200.0 + i as f32 * (BOX_SIZE + 5.0), 0.0),
+			..Default::default()
+		})
+		.insert_bundle(RigidBodyBundle {
+			kinematic: KinematicBundle {
+				forces: ForcesBundle {
+					resistance: Resistance::from_shape(1.0, &shape),
+					..default()
+				},
+				..default()
+			},
+			collider: ColliderBundle::new(shape),
+			..default()
+		});
+	}
+}
@@ -0,0 +1,95 @@
+use bevy::{prelude::*, math::vec2};
+use bevy_prototype_lyon::prelude::*;
+use butsuri::prelude::*;
+
+const LAUNCH_SPEED: f32 = 400.0;
+const LAUNCHER: (f32, f32) = (-300.0, -150.0);
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			..default()
+		})
+		// Also adds the plugin of bevy_prototype_lyon used to draw the path
+		.add_plugin(DebugPlugin)
+		.insert_resource(Gravity(Vec2::Y * -300.0))
+		.insert_resource(Aim(0.8))
+		.add_startup_system(setup)
+		.add_system(aim)
+		.run();
+}
+
+/// Launch angle in radians, changed with the w/s keys
+struct Aim(f32);
+
+#[derive(Component)]
+struct PredictedPath;
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Ground and a wall in the way
+	for (position, shape) in [
+		(vec2(0.0, -200.0), ColliderShape::Square(400.0, 20.0)),
+		(vec2(100.0, -80.0), ColliderShape::Square(20.0, 100.0)),
+	] {
+		commands.spawn_bundle(TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))))
+			.insert_bundle(RigidBodyBundle {
+				collider: ColliderBundle::new(shape),
+				body: Body::Static,
+				..default()
+			});
+	}
+
+	commands.spawn_bundle(GeometryBuilder::build_as(
+		&PathBuilder::new().build(),
+		DrawMode::Stroke(StrokeMode::color(Color::YELLOW)),
+		Transform::default(),
+	))
+	.insert(PredictedPath);
+}
+
+fn aim(
+	mut commands: Commands,
+	keyboard: Res<Input<KeyCode>>,
+	time: Res<Time>,
+	mut aim: ResMut<Aim>,
+	predictor: TrajectoryPredictor,
+	mut path: Query<&mut Path, With<PredictedPath>>,
+) {
+	if keyboard.pressed(KeyCode::W) { aim.0 += time.delta_seconds(); }
+	if keyboard.pressed(KeyCode::S) { aim.0 -= time.delta_seconds(); }
+	let velocity = vec2(aim.0.cos(), aim.0.sin()) * LAUNCH_SPEED;
+	let shape = ColliderShape::Circle(8.0);
+
+	// Preview of the next two seconds or until the ball hits something
+	let projectile = Projectile::new(LAUNCHER.into(), velocity).with_collider(shape.clone());
+	let trajectory = predictor.predict(&projectile, 120, 1.0 / 60.0, Some(&|_| true));
+	let mut builder = PathBuilder::new();
+	builder.move_to(trajectory.points[0]);
+	for &point in &trajectory.points[1..] {
+		builder.line_to(point);
+	}
+	*path.single_mut() = builder.build();
+
+	// Fire with space
+	if keyboard.just_pressed(KeyCode::Space) {
+		commands.spawn_bundle(TransformBundle::from_transform(Transform::from_translation(Vec2::from(LAUNCHER).extend(0.0))))
+			.insert_bundle(RigidBodyBundle {
+				kinematic: KinematicBundle {
+					dynamic: DynamicBundle {
+						velocity: Velocity { linear: velocity, ..default() },
+						..default()
+					},
+					forces: ForcesBundle {
+						resistance: Resistance::from_shape(1.0, &shape),
+						..default()
+					},
+				},
+				collider: ColliderBundle::new(shape),
+				..default()
+			});
+	}
+}
@@ -0,0 +1,72 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			substeps: 4,
+			..default()
+		})
+		.add_plugin(DebugPlugin)
+		.add_plugin(MouseDragPlugin::default())
+		.insert_resource(Gravity(Vec2::Y * -500.0))
+		.add_startup_system(setup)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	// Pool with a slow current to the right
+	commands.spawn_bundle(SpriteBundle {
+		sprite: Sprite {
+			custom_size: Some(vec2(800.0, 300.0)),
+			color: Color::rgba(0.2, 0.4, 1.0, 0.5),
+			..Default::default()
+		},
+		transform: Transform::from_xyz(0.0, -150.0, 0.0),
+		..Default::default()
+	})
+	.insert_bundle(ColliderBundle::new(ColliderShape::Square(400.0, 150.0)))
+	.insert(FluidVolume {
+		density: 1.0,
+		linear_drag: 2.0,
+		angular_drag: 2.0,
+		flow: vec2(20.0, 0.0),
+	});
+
+	// Bodies with different densities, the lighter ones float higher
+	let bodies = [
+		(vec2(-250.0, 100.0), ColliderShape::Square(20.0, 20.0), 0.2),
+		(vec2(-100.0, 100.0), ColliderShape::Square(20.0, 20.0), 0.7),
+		(vec2(  50.0, 100.0), ColliderShape::Circle(25.0), 0.5),
+		(vec2( 200.0, 150.0), ColliderShape::Square(60.0, 10.0), 0.5),
+	];
+	for (position, shape, density) in bodies {
+		let mass = density * area(&shape);
+		commands.spawn_bundle(TransformBundle::from_transform(
+			Transform::from_translation(position.extend(0.0)).with_rotation(Quat::from_rotation_z(0.5))
+		))
+		.insert_bundle(RigidBodyBundle {
+			kinematic: KinematicBundle {
+				forces: ForcesBundle {
+					resistance: Resistance::from_shape(mass, &shape),
+					..default()
+				},
+				..default()
+			},
+			collider: ColliderBundle::new(shape),
+			..default()
+		});
+	}
+}
+
+fn area(shape: &ColliderShape) -> f32 {
+	match *shape {
+		ColliderShape::Square(w, h) => 4.0 * w * h,
+		ColliderShape::Circle(radius) => std::f32::consts::PI * radius * radius,
+		_ => 0.0,
+	}
+}
@@ -0,0 +1,56 @@
+use bevy::{prelude::*, math::vec2};
+use butsuri::prelude::*;
+use rand::{thread_rng, Rng};
+
+fn main() {
+	App::new()
+		.add_plugins(DefaultPlugins)
+		.add_plugin(PhysicsPlugin {
+			gravity_effect: GravityEffect::Acceleration,
+			..default()
+		})
+		.add_plugin(DebugPlugin)
+		.insert_resource(Gravity(Vec2::Y * -100.0))
+		.add_startup_system(setup)
+		.add_system(respawn)
+		.run();
+}
+
+fn setup(mut commands: Commands) {
+	commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+	let zones = [
+		(vec2(-300.0, 0.0), ColliderShape::Square(75.0, 200.0), ForceZone::Wind { acceleration: vec2(0.0, 150.0), velocity: Vec2::ZERO }),
+		(vec2(-100.0, 0.0), ColliderShape::Square(75.0, 200.0), ForceZone::Current { velocity: vec2(100.0, 0.0), drag: 2.0 }),
+		(vec2( 100.0, 0.0), ColliderShape::Circle(100.0), ForceZone::Vortex { speed: 150.0, drag: 3.0, pull: 50.0 }),
+		(vec2( 300.0, 0.0), ColliderShape::Square(75.0, 200.0), ForceZone::Turbulence { strength: 300.0, scale: 50.0, frequency: 1.0 }),
+	];
+	for (position, shape, zone) in zones {
+		commands.spawn_bundle(TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))))
+			.insert_bundle(ColliderBundle::new(shape))
+			.insert(zone);
+	}
+
+	for i in 0..40 {
+		let shape = ColliderShape::Circle(5.0);
+		commands.spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(-350.0 + i as f32 * 18.0, 250.0, 0.0)))
+			.insert_bundle(KinematicBundle {
+				forces: ForcesBundle {
+					resistance: Resistance::from_shape(1.0, &shape),
+					..default()
+				},
+				..default()
+			})
+			.insert_bundle(ColliderBundle::new(shape));
+	}
+}
+
+/// Moves the balls that fall out of the screen back to the top
+fn respawn(mut query: Query<(&mut Transform, &mut Velocity)>) {
+	for (mut transform, mut velocity) in query.iter_mut() {
+		if transform.translation.y < -300.0 || transform.translation.x.abs() > 500.0 {
+			transform.translation = vec2(thread_rng().gen_range(-350.0..350.0), 250.0).extend(0.0);
+			velocity.linear = Vec2::ZERO;
+		}
+	}
+}
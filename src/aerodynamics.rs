@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+// F = -(û*drag*(n·û) + n⊥*lift) * (n·û) * |u|^2 * length
+/// Air resistance computed on every edge of the [`ColliderShape`] that faces the relative airflow
+/// and applied at its midpoint, so unlike [`Drag`] the shape and the rotation matter: a plate
+/// falling flat is slowed down, a plate moving along its length barely is and a tilted plate
+/// glides. The air moves with the [`ForceZone::flow`] of the zones that overlap the body
+/// Only works with `Square` and `Polygon` shapes
+/// Required components for work: [`AccumulatedForce`], [`Velocity`], [`ColliderShape`]
+#[derive(Default, Component, Clone, Copy)]
+pub struct Aerodynamics {
+	/// Force per unit of edge length at 1 unit/s perpendicular to the relative airflow, it is
+	/// strongest when the edge meets the air at 45°
+	pub lift: f32,
+	/// Force per unit of edge length at 1 unit/s against the relative airflow, it grows with the
+	/// square of how much the edge faces the air
+	pub drag: f32,
+}
+
+impl Aerodynamics {
+	/// Flat plate where the air only pushes along the normal of the edges, same as `lift` and
+	/// `drag` with the same value
+	pub fn plate(coefficient: f32) -> Self {
+		Self { lift: coefficient, drag: coefficient }
+	}
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn aerodynamics(
+	zones: Query<(&ForceZone, &Transform, &CollisionInfo)>,
+	mut bodies: Query<(Entity, &Aerodynamics, &Transform, &ColliderShape, &Velocity, &mut AccumulatedForce, Option<&Body>)>,
+) {
+	for (entity, aerodynamics, transform, shape, velocity, mut accumulated, body) in bodies.iter_mut() {
+		if body == Some(&Body::Static) { continue; }
+		if !matches!(shape, ColliderShape::Square(..) | ColliderShape::Polygon(_)) { continue; }
+		let vertices = shape.as_collider(GlobalTransform::from(*transform)).get_positions();
+		let flows: Vec<_> = zones.iter()
+			.filter(|(.., info)| info.sources.contains(&entity))
+			.map(|(zone, center, _)| (zone, center.translation.truncate()))
+			.collect();
+		let position = transform.translation.truncate();
+		// Positive for counterclockwise vertices, used to point the normals outside
+		let winding = vertices.iter().enumerate()
+			.fold(0.0, |sum, (i, p1)| sum + p1.perp_dot(vertices[(i + 1) % vertices.len()]))
+			.signum();
+
+		for (i, &p1) in vertices.iter().enumerate() {
+			let edge = vertices[(i + 1) % vertices.len()] - p1;
+			let length = edge.length();
+			if length < f32::EPSILON { continue; }
+			let normal = -edge.perp() / length * winding;
+			let middle = p1 + edge * 0.5;
+
+			let air = flows.iter()
+				.filter_map(|(zone, center)| zone.flow(*center, middle))
+				.fold(Vec2::ZERO, |sum, flow| sum + flow);
+			let relative = velocity.linear + velocity.angular * (middle - position).perp() - air;
+			let speed = relative.length();
+			if speed < f32::EPSILON { continue; }
+			let direction = relative / speed;
+			// Only the edges moving into the air are pushed
+			let facing = normal.dot(direction);
+			if facing <= 0.0 { continue; }
+
+			let across = normal - direction * facing;
+			let force = -(direction * aerodynamics.drag * facing + across * aerodynamics.lift) * facing * speed * speed * length;
+			accumulated.add_at_point(force, middle - position);
+		}
+	}
+}
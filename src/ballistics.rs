@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use crate::Gravity;
+
+/// Samples of the flight time searched by [`Gravity::intercept`]
+const INTERCEPT_SAMPLES: u32 = 64;
+/// Steps each root of [`Gravity::intercept`] is refined after it is found
+const INTERCEPT_REFINEMENT: u32 = 24;
+
+/// Launch velocity and the seconds it takes to reach the target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaunchArc {
+	pub velocity: Vec2,
+	pub time: f32,
+}
+
+/// The two launch arcs with the same speed that reach a target, when there is a single solution
+/// both are the same
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaunchArcs {
+	/// Flattest arc, it arrives first
+	pub low: LaunchArc,
+	/// Steepest arc, it arrives last
+	pub high: LaunchArc,
+}
+
+// p(t) = from + v*t + g*t²/2
+/// Ballistic solutions without drag, they are exact with [`GravityEffect::Acceleration`](crate::GravityEffect::Acceleration)
+/// and the projectile must not have [`Drag`](crate::kinematic::Drag), multiply the gravity by
+/// the [`GravityScale`](crate::GravityScale) of the projectile before solving
+impl Gravity {
+	/// Velocities of `speed` units/s that go from `from` to `to`, [`None`] if the target is out of reach
+	pub fn launch_arcs(&self, from: Vec2, to: Vec2, speed: f32) -> Option<LaunchArcs> {
+		let (g, d) = (**self, to - from);
+		let arc = |time: f32| LaunchArc { velocity: self.launch_velocity(from, to, time), time };
+		if g.length_squared() < f32::EPSILON {
+			if speed <= 0.0 { return None; }
+			let straight = arc(d.length() / speed);
+			return Some(LaunchArcs { low: straight, high: straight });
+		}
+		// |d - g*t²/2|² = speed²*t² is a quadratic in t²
+		let b = d.dot(g) + speed * speed;
+		let discriminant = b * b - g.length_squared() * d.length_squared();
+		if discriminant < 0.0 { return None; }
+		let a = 0.5 * g.length_squared();
+		let (short, long) = ((b - discriminant.sqrt()) / a, (b + discriminant.sqrt()) / a);
+		if long <= 0.0 { return None; }
+		let short = if short > 0.0 { short } else { long };
+		Some(LaunchArcs { low: arc(short.sqrt()), high: arc(long.sqrt()) })
+	}
+
+	/// Velocity that goes from `from` to `to` in `time` seconds
+	pub fn launch_velocity(&self, from: Vec2, to: Vec2, time: f32) -> Vec2 {
+		(to - from - 0.5 * **self * time * time) / time.max(f32::EPSILON)
+	}
+
+	/// Velocities of `speed` units/s that hit a target at `target` moving with a constant
+	/// `target_velocity`, only flights shorter than `max_time` seconds are searched. [`None`] if
+	/// the target can not be reached in time
+	pub fn intercept(
+		&self,
+		from: Vec2,
+		target: Vec2,
+		target_velocity: Vec2,
+		speed: f32,
+		max_time: f32,
+	) -> Option<LaunchArcs> {
+		if target_velocity == Vec2::ZERO {
+			return self.launch_arcs(from, target, speed)
+				.filter(|arcs| arcs.low.time <= max_time)
+				.map(|arcs| if arcs.high.time <= max_time { arcs } else { LaunchArcs { high: arcs.low, ..arcs } });
+		}
+		// Negative while the projectile can reach the future position of the target
+		let g = **self;
+		let gap = |t: f32| (target - from + target_velocity * t - 0.5 * g * t * t).length_squared() - speed * speed * t * t;
+		let arc = |time: f32| LaunchArc { velocity: self.launch_velocity(from, target + target_velocity * time, time), time };
+
+		let mut roots = vec![];
+		let step = max_time / INTERCEPT_SAMPLES as f32;
+		for i in 0..INTERCEPT_SAMPLES {
+			let (mut start, mut end) = (i as f32 * step, (i + 1) as f32 * step);
+			let positive = gap(start) > 0.0;
+			if (gap(end) > 0.0) == positive { continue; }
+			for _ in 0..INTERCEPT_REFINEMENT {
+				let middle = (start + end) * 0.5;
+				if (gap(middle) > 0.0) == positive { start = middle; } else { end = middle; }
+			}
+			roots.push((start + end) * 0.5);
+		}
+		let (&first, &last) = (roots.first()?, roots.last()?);
+		Some(LaunchArcs { low: arc(first), high: arc(last) })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use bevy::math::vec2;
+	use super::*;
+
+	fn position(gravity: &Gravity, from: Vec2, arc: LaunchArc) -> Vec2 {
+		from + arc.velocity * arc.time + 0.5 * **gravity * arc.time * arc.time
+	}
+
+	#[test]
+	fn both_arcs_hit_the_target() {
+		let gravity = Gravity(vec2(0.0, -9.8));
+		let (from, to, speed) = (vec2(0.0, 0.0), vec2(30.0, 5.0), 25.0);
+		let arcs = gravity.launch_arcs(from, to, speed).unwrap();
+		assert!(arcs.low.time < arcs.high.time);
+		for arc in [arcs.low, arcs.high] {
+			assert!((arc.velocity.length() - speed).abs() < 1e-3);
+			assert!(position(&gravity, from, arc).abs_diff_eq(to, 1e-2));
+		}
+	}
+
+	#[test]
+	fn target_out_of_range() {
+		// The farthest a speed of 10 units/s reaches on flat ground is 10²/9.8 units
+		let gravity = Gravity(vec2(0.0, -9.8));
+		assert!(gravity.launch_arcs(Vec2::ZERO, vec2(20.0, 0.0), 10.0).is_none());
+	}
+
+	#[test]
+	fn intercept_moving_target() {
+		// Without gravity |(100, 30t)| = 50t, so 100² = (50² - 30²) t² and t = 2.5
+		let gravity = Gravity(Vec2::ZERO);
+		let (target, target_velocity) = (vec2(100.0, 0.0), vec2(0.0, 30.0));
+		let arcs = gravity.intercept(Vec2::ZERO, target, target_velocity, 50.0, 10.0).unwrap();
+		assert!((arcs.low.time - 2.5).abs() < 1e-3);
+		assert!(position(&gravity, Vec2::ZERO, arcs.low).abs_diff_eq(target + target_velocity * arcs.low.time, 1e-2));
+
+		// With gravity every arc still meets the target where it is at that time
+		let gravity = Gravity(vec2(0.0, -9.8));
+		let arcs = gravity.intercept(Vec2::ZERO, vec2(40.0, 0.0), vec2(-5.0, 0.0), 30.0, 10.0).unwrap();
+		for arc in [arcs.low, arcs.high] {
+			assert!((arc.velocity.length() - 30.0).abs() < 1e-2);
+			assert!(position(&gravity, Vec2::ZERO, arc).abs_diff_eq(vec2(40.0 - 5.0 * arc.time, 0.0), 1e-2));
+		}
+	}
+}
@@ -1,10 +1,17 @@
 use bevy::prelude::*;
 
+/// Fraction of the size of a shape along a direction under which two vertices are considered to be
+/// at the same depth, it lets flat edges resting on each other be treated as an edge instead of a
+/// single vertex at any world scale
+pub(crate) const SUPPORT_TOLERANCE: f32 = 0.02;
+
 pub(crate) trait Collider {
 	fn get_normals(&self) -> Vec<Vec2> { vec![] } 
 	fn get_positions(&self) -> Vec<Vec2>;
-	fn axes_from_position(&self, other_positions: &Vec<Vec2>) -> Vec<Vec2>;
+	fn axes_from_position(&self, other_positions: &[Vec2]) -> Vec<Vec2>;
 	fn range_along_axis(&self, axis_proj: Vec2) -> (f32, f32);
+	/// Points of the shape farthest along `direction`, more than one if it is an edge
+	fn support(&self, direction: Vec2) -> Vec<Vec2>;
 	fn collide(&self, other: Box<dyn Collider>) -> bool {
 		let position_axes = self.axes_from_position(&other.get_positions());
 		let self_normals = self.get_normals();
@@ -22,5 +29,29 @@ pub(crate) trait Collider {
 		}
 		true
 	}
-}
+	/// Minimum translation that separates `other` from `self`, the normal goes from `self` to `other`
+	fn penetration(&self, other: &dyn Collider) -> Option<(Vec2, f32)> {
+		let position_axes = self.axes_from_position(&other.get_positions());
+		let self_normals = self.get_normals();
+		let other_normals = other.get_normals();
+
+		let axes = [position_axes, self_normals, other_normals].concat();
 
+		let mut penetration: Option<(Vec2, f32)> = None;
+		for axis in axes.into_iter().filter(|&axis| axis != Vec2::ZERO) {
+			let (amin, amax) = self .range_along_axis(axis);
+			let (bmin, bmax) = other.range_along_axis(axis);
+
+			let forward  = amax - bmin;
+			let backward = bmax - amin;
+			if forward < 0.0 || backward < 0.0 {
+				return None;
+			}
+			let (normal, depth) = if forward < backward { (axis, forward) } else { (-axis, backward) };
+			if penetration.is_none_or(|(_, d)| depth < d) {
+				penetration = Some((normal, depth));
+			}
+		}
+		penetration
+	}
+}
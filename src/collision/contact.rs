@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use super::ColliderShape;
+
+/// Contact between two colliders
+pub(crate) struct Contact {
+	/// Direction in which the second collider has to move to separate from the first one
+	pub normal: Vec2,
+	/// How deep the colliders overlap along the normal
+	pub depth: f32,
+	/// World position where the colliders touch
+	pub point: Vec2,
+}
+
+impl ColliderShape {
+	pub(crate) fn contact(
+		&self,
+		trans: GlobalTransform,
+		other: &ColliderShape,
+		other_trans: GlobalTransform,
+	) -> Option<Contact> {
+		let acoll = self.as_collider(trans);
+		let bcoll = other.as_collider(other_trans);
+		let (normal, depth) = acoll.penetration(bcoll.as_ref())?;
+		let point = contact_point(&acoll.support(normal), &bcoll.support(-normal), normal);
+		Some(Contact { normal, depth, point })
+	}
+}
+
+fn contact_point(a: &[Vec2], b: &[Vec2], normal: Vec2) -> Vec2 {
+	// Vertex against edge
+	if b.len() == 1 { return b[0]; }
+	if a.len() == 1 { return a[0]; }
+	// Edge against edge, middle of the part where both edges overlap
+	let tangent = normal.perp();
+	let range = |points: &[Vec2]| points.iter()
+		.map(|p| p.dot(tangent))
+		.fold((f32::INFINITY, -f32::INFINITY), |(min, max), d| (min.min(d), max.max(d)));
+	let (amin, amax) = range(a);
+	let (bmin, bmax) = range(b);
+	let middle = (amin.max(bmin) + amax.min(bmax)) * 0.5;
+	let average = |points: &[Vec2]| points.iter().map(|p| p.dot(normal)).sum::<f32>() / points.len() as f32;
+	let depth = (average(a) + average(b)) * 0.5;
+	tangent * middle + normal * depth
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tilted_edge_contact_at_any_scale() {
+		for scale in [1.0, 100.0] {
+			let ground = ColliderShape::Square(10.0 * scale, scale);
+			let block = ColliderShape::Square(scale, scale);
+			// Slightly tilted and sunk into the ground
+			let transform = Transform::from_xyz(0.0, 1.95 * scale, 0.0).with_rotation(Quat::from_rotation_z(0.005));
+			let contact = ground.contact(GlobalTransform::identity(), &block, GlobalTransform::from(transform)).unwrap();
+			assert!(contact.normal.abs_diff_eq(Vec2::Y, 1e-3));
+			// The middle of the bottom edge, not one of its corners
+			assert!(contact.point.x.abs() < 0.1 * scale, "{} at scale {scale}", contact.point);
+		}
+	}
+}
@@ -1,4 +1,6 @@
 mod collider;
+mod contact; pub(crate) use contact::Contact;
+mod query; pub use query::*;
 pub mod shape {
 	use super::collider::*;
 	//mod aabb; pub(super) use aabb::AABB;
@@ -30,7 +32,7 @@ pub struct CollisionInfo {
 	pub sources: HashSet<Entity>,
 }
 
-#[derive(Default, Component)]
+#[derive(Default, Component, Clone)]
 pub enum ColliderShape {
 	#[default]
 	Point,
@@ -41,7 +43,7 @@ pub enum ColliderShape {
 }
 
 impl ColliderShape {
-	fn as_collider(&self, trans: GlobalTransform) -> Box<dyn collider::Collider> {
+	pub(crate) fn as_collider(&self, trans: GlobalTransform) -> Box<dyn collider::Collider> {
 		match self {
 			&Self::Circle(radius) => Box::new(shape::Circle { radius, position: trans.translation.truncate() }),
 			&Self::Square(w, h) => Box::new(shape::Polygon::square(w, h, trans)),
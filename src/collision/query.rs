@@ -0,0 +1,149 @@
+use bevy::{prelude::*, ecs::system::SystemParam};
+use crate::rigid_body::Body;
+use super::{ColliderShape, shape};
+
+/// Steps a shape cast is refined after it finds the first overlap
+const CAST_REFINEMENT: u32 = 8;
+
+/// Finds the colliders in a region of the world, use it as a system parameter
+#[derive(SystemParam)]
+pub struct SpatialQuery<'w, 's> {
+	colliders: Query<'w, 's, (Entity, &'static Transform, &'static ColliderShape, Option<&'static Body>)>,
+}
+
+/// First collider found by [`SpatialQuery::raycast`] or [`SpatialQuery::shape_cast`]
+#[derive(Clone, Copy)]
+pub struct CastHit {
+	pub entity: Entity,
+	/// Position of the ray or of the center of the shape when it touches the collider
+	pub point: Vec2,
+	/// Fraction of the way from the start to the end where the hit happens
+	pub fraction: f32,
+}
+
+impl<'w, 's> SpatialQuery<'w, 's> {
+	/// Entities whose collider contains `point`
+	pub fn point(&self, point: Vec2) -> Vec<Entity> {
+		self.circle(point, 0.0)
+	}
+
+	/// Entities whose collider overlaps the circle of `radius` around `center`
+	pub fn circle(&self, center: Vec2, radius: f32) -> Vec<Entity> {
+		self.colliders.iter()
+			.filter(|(_, transform, shape, _)| {
+				if !matches!(shape, ColliderShape::Circle(_) | ColliderShape::Square(..) | ColliderShape::Polygon(_)) { return false; }
+				let collider = shape.as_collider(GlobalTransform::from(**transform));
+				collider.collide(Box::new(shape::Circle { position: center, radius }))
+			})
+			.map(|(entity, ..)| entity)
+			.collect()
+	}
+
+	/// First rigid body crossed by the segment from `from` to `to`, if `from` is inside of a body
+	/// it is hit at the start, only the entities accepted by `filter` are tested
+	pub fn raycast(&self, from: Vec2, to: Vec2, filter: impl Fn(Entity) -> bool) -> Option<CastHit> {
+		self.bodies(filter)
+			.filter_map(|(entity, transform, shape)| {
+				let fraction = ray_fraction(shape, transform, from, to)?;
+				Some(CastHit { entity, point: from.lerp(to, fraction), fraction })
+			})
+			.min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+	}
+
+	/// First rigid body touched by `shape` moving without rotating from `from` to `to`, only the
+	/// entities accepted by `filter` are tested
+	pub fn shape_cast(&self, shape: &ColliderShape, from: Vec2, to: Vec2, filter: impl Fn(Entity) -> bool) -> Option<CastHit> {
+		let overlaps = |other: &ColliderShape, transform: GlobalTransform, fraction: f32| {
+			let position = from.lerp(to, fraction);
+			shape.as_collider(GlobalTransform::from_translation(position.extend(0.0)))
+				.collide(other.as_collider(transform))
+		};
+		// Samples close enough so the shape can not jump over a collider of its size
+		let collider = shape.as_collider(GlobalTransform::identity());
+		let (min_x, max_x) = collider.range_along_axis(Vec2::X);
+		let (min_y, max_y) = collider.range_along_axis(Vec2::Y);
+		let step = ((max_x - min_x).min(max_y - min_y) * 0.5).max(f32::EPSILON);
+		let samples = ((to - from).length() / step).ceil().max(1.0) as u32;
+
+		self.bodies(filter)
+			.filter_map(|(entity, transform, other)| {
+				let transform = GlobalTransform::from(transform);
+				let hit = (0..=samples).map(|i| i as f32 / samples as f32)
+					.find(|&fraction| overlaps(other, transform, fraction))?;
+				// Bisection between the last free sample and the first overlapping one
+				let (mut free, mut hit) = ((hit - 1.0 / samples as f32).max(0.0), hit);
+				for _ in 0..CAST_REFINEMENT {
+					let middle = (free + hit) * 0.5;
+					if overlaps(other, transform, middle) { hit = middle; } else { free = middle; }
+				}
+				Some(CastHit { entity, point: from.lerp(to, hit), fraction: hit })
+			})
+			.min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+	}
+
+	/// Colliders of the rigid bodies accepted by `filter`
+	fn bodies(&self, filter: impl Fn(Entity) -> bool) -> impl Iterator<Item = (Entity, Transform, &ColliderShape)> {
+		self.colliders.iter()
+			.filter(move |&(entity, _, _, body)| body.is_some() && filter(entity))
+			.map(|(entity, transform, shape, _)| (entity, *transform, shape))
+	}
+}
+
+/// Fraction of the segment from `from` to `to` where it enters the shape
+fn ray_fraction(shape: &ColliderShape, transform: Transform, from: Vec2, to: Vec2) -> Option<f32> {
+	let direction = to - from;
+	match *shape {
+		ColliderShape::Circle(radius) => {
+			let offset = from - transform.translation.truncate();
+			let c = offset.length_squared() - radius * radius;
+			if c <= 0.0 { return Some(0.0); }
+			// |offset + direction * t| = radius
+			let a = direction.length_squared();
+			let b = offset.dot(direction);
+			let discriminant = b * b - a * c;
+			if a == 0.0 || discriminant < 0.0 { return None; }
+			let t = (-b - discriminant.sqrt()) / a;
+			(0.0..=1.0).contains(&t).then_some(t)
+		}
+		ColliderShape::Square(..) | ColliderShape::Polygon(_) => {
+			let vertices = shape.as_collider(GlobalTransform::from(transform)).get_positions();
+			let inside = shape.as_collider(GlobalTransform::from(transform))
+				.collide(Box::new(shape::Circle { position: from, radius: 0.0 }));
+			if inside { return Some(0.0); }
+			vertices.iter().enumerate()
+				.filter_map(|(i, &p1)| {
+					let edge = vertices[(i + 1) % vertices.len()] - p1;
+					let denominator = direction.perp_dot(edge);
+					if denominator.abs() < f32::EPSILON { return None; }
+					let t = (p1 - from).perp_dot(edge) / denominator;
+					let u = (p1 - from).perp_dot(direction) / denominator;
+					((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+				})
+				.min_by(f32::total_cmp)
+		}
+		_ => None,
+	}
+}
+
+/// Point of the shape closest to `point`, `point` itself if it is inside
+pub(crate) fn closest_point(shape: &ColliderShape, transform: Transform, point: Vec2) -> Option<Vec2> {
+	match *shape {
+		ColliderShape::Circle(radius) => {
+			let center = transform.translation.truncate();
+			Some(center + (point - center).clamp_length_max(radius))
+		}
+		ColliderShape::Square(..) | ColliderShape::Polygon(_) => {
+			let collider = shape.as_collider(GlobalTransform::from(transform));
+			if collider.collide(Box::new(shape::Circle { position: point, radius: 0.0 })) { return Some(point); }
+			let vertices = collider.get_positions();
+			vertices.iter().enumerate()
+				.map(|(i, &p1)| {
+					let edge = vertices[(i + 1) % vertices.len()] - p1;
+					let t = ((point - p1).dot(edge) / edge.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+					p1 + edge * t
+				})
+				.min_by(|a, b| a.distance_squared(point).total_cmp(&b.distance_squared(point)))
+		}
+		_ => None,
+	}
+}
@@ -11,7 +11,7 @@ impl Collider for Circle {
 		vec![self.position]
 	}
 
-	fn axes_from_position(&self, other_positions: &Vec<Vec2>) -> Vec<Vec2> {
+	fn axes_from_position(&self, other_positions: &[Vec2]) -> Vec<Vec2> {
 		other_positions.iter()
 			.map(|&p| (p-self.position)
 			.normalize_or_zero())
@@ -23,5 +23,8 @@ impl Collider for Circle {
 		let vmax = self.position + axis_proj*self.radius;
 		(vmin.dot(axis_proj), vmax.dot(axis_proj))
 	}
-}
 
+	fn support(&self, direction: Vec2) -> Vec<Vec2> {
+		vec![self.position + direction.normalize_or_zero() * self.radius]
+	}
+}
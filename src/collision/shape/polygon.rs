@@ -1,5 +1,5 @@
 use bevy::{prelude::*, math::vec2};
-use super::Collider;
+use super::{Collider, SUPPORT_TOLERANCE};
 
 pub struct Polygon {
 	pub vertices: Vec<Vec2>,
@@ -45,7 +45,7 @@ impl Collider for Polygon {
 		self.vertices.clone()
 	}
 
-	fn axes_from_position(&self, other_positions: &Vec<Vec2>) -> Vec<Vec2> {
+	fn axes_from_position(&self, other_positions: &[Vec2]) -> Vec<Vec2> {
 		if other_positions.len() > 1 {
 			return vec![];
 		}
@@ -74,5 +74,13 @@ impl Collider for Polygon {
 		}
 		(min, max)
 	}
-}
 
+	fn support(&self, direction: Vec2) -> Vec<Vec2> {
+		let (min, max) = self.range_along_axis(direction);
+		let tolerance = (max - min) * SUPPORT_TOLERANCE;
+		self.vertices.iter()
+			.copied()
+			.filter(|v| max - v.dot(direction) <= tolerance)
+			.collect()
+	}
+}
@@ -0,0 +1,110 @@
+use std::f32::consts::PI;
+use bevy::prelude::*;
+use crate::prelude::*;
+
+// F = m * (kp*e - kd*v)
+/// Drives a dynamic body toward a target position and rotation with a PD controller, it only
+/// applies forces so the body still reacts to collisions and joints. The gains are accelerations
+/// so the same values work with any mass, under gravity the body rests a bit below the target
+/// Required components for work: [`AccumulatedForce`], [`Resistance`], [`Velocity`]
+#[derive(Component, Clone, Copy)]
+pub struct FollowTarget {
+	pub position: Vec2,
+	/// Target rotation in radians, [`None`] leaves the rotation free
+	pub rotation: Option<f32>,
+	/// Proportional and derivative gains of the position
+	pub linear_gains: (f32, f32),
+	/// Proportional and derivative gains of the rotation
+	pub angular_gains: (f32, f32),
+	pub max_force: f32,
+	pub max_torque: f32,
+}
+
+impl FollowTarget {
+	/// Target at `position` with critically damped gains that settle in about a second
+	pub fn new(position: Vec2) -> Self {
+		Self {
+			position,
+			rotation: None,
+			linear_gains: (25.0, 10.0),
+			angular_gains: (25.0, 10.0),
+			max_force: f32::INFINITY,
+			max_torque: f32::INFINITY,
+		}
+	}
+
+	pub fn with_rotation(mut self, rotation: f32) -> Self {
+		self.rotation = Some(rotation);
+		self
+	}
+
+	pub fn with_max_force(mut self, max_force: f32, max_torque: f32) -> Self {
+		self.max_force = max_force;
+		self.max_torque = max_torque;
+		self
+	}
+}
+
+pub(crate) fn follow_target(
+	mut query: Query<(&FollowTarget, &Transform, &Velocity, &Resistance, &mut AccumulatedForce)>
+) {
+	for (target, transform, velocity, resistance, mut accumulated) in query.iter_mut() {
+		let (kp, kd) = target.linear_gains;
+		let error = target.position - transform.translation.truncate();
+		let force = resistance.mass * (kp * error - kd * velocity.linear);
+		accumulated.linear += force.clamp_length_max(target.max_force);
+
+		if let Some(rotation) = target.rotation {
+			let (kp, kd) = target.angular_gains;
+			// Shortest way around the circle
+			let error = (rotation - transform.rotation.to_scaled_axis().z + PI).rem_euclid(2.0 * PI) - PI;
+			let torque = resistance.inertia * (kp * error - kd * velocity.angular);
+			accumulated.angular += torque.clamp(-target.max_torque, target.max_torque);
+		}
+	}
+}
+
+/// Engine mounted on a body, it pushes the body along `direction` from `position`, both are in the
+/// local space of the body so the thruster turns with it
+#[derive(Clone, Copy)]
+pub struct Thruster {
+	pub position: Vec2,
+	/// Unit vector the force pushes the body to, the exhaust goes the other way
+	pub direction: Vec2,
+	pub max_force: f32,
+	/// Fraction of the `max_force` applied, from `0.0` to `1.0`
+	pub throttle: f32,
+}
+
+impl Thruster {
+	/// Thruster turned off
+	pub fn new(position: Vec2, direction: Vec2, max_force: f32) -> Self {
+		Self {
+			position,
+			direction: direction.normalize_or_zero(),
+			max_force,
+			throttle: 0.0,
+		}
+	}
+
+	/// Force in the local space of the body at the current throttle
+	pub fn force(&self) -> Vec2 {
+		self.direction * self.max_force * self.throttle.clamp(0.0, 1.0)
+	}
+}
+
+/// Set of [`Thruster`]s of a spaceship or a drone, change the throttle of each one to steer it,
+/// a thruster away from the center of mass also turns the body
+/// Required components for work: [`AccumulatedForce`]
+#[derive(Default, Component, Clone, Deref, DerefMut)]
+pub struct Thrusters(pub Vec<Thruster>);
+
+pub(crate) fn apply_thrusters(mut query: Query<(&Thrusters, &Transform, &mut AccumulatedForce)>) {
+	for (thrusters, transform, mut accumulated) in query.iter_mut() {
+		for thruster in thrusters.iter() {
+			let force = transform.rotation * thruster.force().extend(0.0);
+			let point = transform.rotation * thruster.position.extend(0.0);
+			accumulated.add_at_point(force.truncate(), point.truncate());
+		}
+	}
+}
@@ -0,0 +1,84 @@
+use bevy::{prelude::*, ecs::system::SystemParam};
+use crate::prelude::*;
+
+/// Pushes the bodies around a point away from it, use it as a system parameter
+#[derive(SystemParam)]
+pub struct Explosions<'w, 's> {
+	spatial: SpatialQuery<'w, 's>,
+	bodies: Query<'w, 's, (
+		&'static Transform,
+		&'static ColliderShape,
+		&'static Resistance,
+		&'static mut Velocity,
+		Option<&'static LockedAxes>,
+	)>,
+	kinds: Query<'w, 's, &'static Body>,
+}
+
+impl<'w, 's> Explosions<'w, 's> {
+	/// Applies an impulse of `impulse` N·s scaled by the `falloff` to every dynamic body accepted
+	/// by `filter` that overlaps the circle of `radius` around `center`, with
+	/// [`Falloff::InverseSquare`] it is the impulse at one world unit from the center. The impulse
+	/// pushes the point of the body closest to the center, so a hit off the center of mass also
+	/// spins the body. Returns the bodies pushed
+	pub fn explode(
+		&mut self,
+		center: Vec2,
+		radius: f32,
+		impulse: f32,
+		falloff: Falloff,
+		filter: impl Fn(Entity) -> bool,
+	) -> Vec<Entity> {
+		self.blast(center, radius, impulse, falloff, filter, false)
+	}
+
+	/// Same as [`Explosions::explode`] but the bodies hidden behind a static collider are not
+	/// pushed, an explosion whose center is inside of a static collider does not push anything
+	pub fn explode_occluded(
+		&mut self,
+		center: Vec2,
+		radius: f32,
+		impulse: f32,
+		falloff: Falloff,
+		filter: impl Fn(Entity) -> bool,
+	) -> Vec<Entity> {
+		self.blast(center, radius, impulse, falloff, filter, true)
+	}
+
+	fn blast(
+		&mut self,
+		center: Vec2,
+		radius: f32,
+		impulse: f32,
+		falloff: Falloff,
+		filter: impl Fn(Entity) -> bool,
+		occluded: bool,
+	) -> Vec<Entity> {
+		let mut pushed = vec![];
+		for entity in self.spatial.circle(center, radius) {
+			if !filter(entity) || self.kinds.get(entity) == Ok(&Body::Static) { continue; }
+			let Ok((transform, shape, ..)) = self.bodies.get(entity) else { continue };
+			let position = transform.translation.truncate();
+			let Some(point) = closest_point(shape, *transform, center) else { continue };
+			if occluded {
+				let is_static = |other: Entity| other != entity && self.kinds.get(other) == Ok(&Body::Static);
+				if self.spatial.raycast(center, point, is_static).is_some() { continue; }
+			}
+
+			// From the inside the body is pushed from its center of mass
+			let offset = point - center;
+			let direction = if offset.length_squared() > f32::EPSILON { offset.normalize() } else { (position - center).normalize_or_zero() };
+			let impulse = direction * impulse * falloff.scale(offset.length(), radius);
+			if impulse == Vec2::ZERO { continue; }
+
+			let Ok((_, _, resistance, mut velocity, locked)) = self.bodies.get_mut(entity) else { continue };
+			let (linear_mask, angular_mask) = locked.map_or((Vec2::ONE, 1.0), |l| (l.linear_mask(), l.angular_mask()));
+			velocity.linear += impulse * linear_mask / resistance.mass;
+			if resistance.inertia > 0.0 {
+				velocity.angular += (point - position).perp_dot(impulse) * angular_mask / resistance.inertia;
+			}
+			pushed.push(entity);
+		}
+		pushed
+	}
+}
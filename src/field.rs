@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// How the strength of a field changes with the distance to its center
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum Falloff {
+	// a = s / d^2
+	/// Like the gravity of a planet, the distance is in world units and not relative to the radius
+	/// so the strength is the one at one unit from the center, and the radius can be infinite. The
+	/// distance is never less than one unit so it does not explode at the center
+	#[default]
+	InverseSquare,
+	/// Full strength at the center and zero at the radius
+	Linear,
+	/// Same strength everywhere inside the radius
+	Constant,
+}
+
+impl Falloff {
+	/// Multiplier of the strength at `distance` from the center of a field of the given `radius`,
+	/// zero outside of it
+	pub fn scale(&self, distance: f32, radius: f32) -> f32 {
+		if distance > radius { return 0.0; }
+		match self {
+			Self::InverseSquare => distance.max(1.0).powi(-2),
+			Self::Linear => if radius > 0.0 { 1.0 - distance / radius } else { 0.0 },
+			Self::Constant => 1.0,
+		}
+	}
+}
+
+/// Pulls the bodies around the entity toward its center, a negative strength pushes them away.
+/// The strength is an acceleration so every body falls the same regardless of its mass, the
+/// [`Integrator`] evaluates it at every position it predicts inside the step. Like the gravity it is
+/// not part of the [`Acceleration`] of the body, and a body resting in the field can sleep until
+/// the field at its position changes
+/// Affects the entities with: [`Resistance`]
+#[derive(Component, Clone, Copy)]
+pub struct Attractor {
+	/// Acceleration at the center, one unit from it with [`Falloff::InverseSquare`]
+	pub strength: f32,
+	/// Distance from the center where the field ends, it can be [`f32::INFINITY`]
+	pub radius: f32,
+	pub falloff: Falloff,
+}
+
+impl Attractor {
+	/// Acceleration of a body at `point` caused by an attractor at `center`
+	pub fn acceleration(&self, center: Vec2, point: Vec2) -> Vec2 {
+		let offset = center - point;
+		let scale = self.falloff.scale(offset.length(), self.radius);
+		offset.normalize_or_zero() * self.strength * scale
+	}
+}
+
+/// Attractors of the current step, the integrator evaluates them at every position it predicts
+#[derive(Default, Deref, DerefMut)]
+pub(crate) struct AttractorField(Vec<(Entity, Vec2, Attractor)>);
+
+impl AttractorField {
+	/// Acceleration of `entity` at `point`, an attractor does not pull itself
+	pub(crate) fn acceleration(&self, entity: Entity, point: Vec2) -> Vec2 {
+		self.iter()
+			.filter(|(source, ..)| *source != entity)
+			.fold(Vec2::ZERO, |sum, (_, center, attractor)| sum + attractor.acceleration(*center, point))
+	}
+}
+
+pub(crate) fn attract(mut field: ResMut<AttractorField>, attractors: Query<(Entity, &Transform, &Attractor)>) {
+	field.clear();
+	field.extend(attractors.iter().map(|(entity, transform, attractor)| (entity, transform.translation.truncate(), *attractor)));
+}
+
+/// Region shaped by the [`ColliderShape`] of the entity that pushes the bodies that overlap it,
+/// like [`Attractor`] every value is an acceleration so the mass of the bodies does not matter
+/// Required components for work: [`ColliderBundle`]
+/// Affects the entities with: [`AccumulatedForce`], [`Resistance`], [`ColliderShape`]
+#[derive(Component, Clone, Copy)]
+pub enum ForceZone {
+	/// Same push everywhere like a fan, `acceleration` pushes every body and `velocity` is the
+	/// speed of the air seen by [`Aerodynamics`], leave the acceleration at zero for bodies
+	/// that already have [`Aerodynamics`] so they are not pushed twice
+	Wind { acceleration: Vec2, velocity: Vec2 },
+	/// Moving water that drags the bodies until they move at `velocity`
+	Current { velocity: Vec2, drag: f32 },
+	/// Fluid spinning around the center of the zone at `speed` units/s, counterclockwise if it is
+	/// positive, `pull` moves the bodies toward the center
+	Vortex { speed: f32, drag: f32, pull: f32 },
+	/// Push that changes smoothly with the position and the time, `scale` is the size in units of
+	/// the gusts and `frequency` how many times per second they change
+	Turbulence { strength: f32, scale: f32, frequency: f32 },
+}
+
+impl ForceZone {
+	/// Velocity of the fluid at `point`, [`None`] if the zone does not move a fluid
+	pub fn flow(&self, center: Vec2, point: Vec2) -> Option<Vec2> {
+		match *self {
+			Self::Wind { velocity, .. } | Self::Current { velocity, .. } => Some(velocity),
+			Self::Vortex { speed, .. } => Some((point - center).perp().normalize_or_zero() * speed),
+			Self::Turbulence { .. } => None,
+		}
+	}
+
+	/// Acceleration of a body at `point` moving at `velocity`, `time` is in seconds
+	pub fn acceleration(&self, center: Vec2, point: Vec2, velocity: Vec2, time: f32) -> Vec2 {
+		let drag = |flow: Vec2, drag: f32| (flow - velocity) * drag;
+		match *self {
+			Self::Wind { acceleration, .. } => acceleration,
+			Self::Current { velocity: flow, drag: c } => drag(flow, c),
+			Self::Vortex { drag: c, pull, .. } => {
+				let flow = self.flow(center, point).unwrap_or_default();
+				drag(flow, c) + (center - point).normalize_or_zero() * pull
+			}
+			Self::Turbulence { strength, scale, frequency } => {
+				let p = (point / scale.max(f32::EPSILON)).extend(time * frequency);
+				// Different offsets so both axes are not equal
+				Vec2::new(noise(p), noise(p + Vec3::splat(31.7))) * strength
+			}
+		}
+	}
+}
+
+pub(crate) fn apply_zones(
+	time: Res<Time>,
+	zones: Query<(&ForceZone, &Transform, &CollisionInfo)>,
+	mut bodies: Query<(&Transform, &Velocity, &Resistance, &mut AccumulatedForce, Option<&Body>)>,
+) {
+	let seconds = time.seconds_since_startup() as f32;
+	for (zone, center, info) in zones.iter() {
+		let center = center.translation.truncate();
+		for &entity in info.sources.iter() {
+			let Ok((transform, velocity, resistance, mut accumulated, body)) = bodies.get_mut(entity) else { continue };
+			if body == Some(&Body::Static) { continue; }
+			let acceleration = zone.acceleration(center, transform.translation.truncate(), velocity.linear, seconds);
+			accumulated.linear += acceleration * resistance.mass;
+		}
+	}
+}
+
+/// Smooth value noise in the range [-1, 1]
+fn noise(p: Vec3) -> f32 {
+	let cell = p.floor();
+	let t = p - cell;
+	// Smoothstep so the gradient is continuous between cells
+	let t = t * t * (Vec3::splat(3.0) - 2.0 * t);
+	let corner = |x: f32, y: f32, z: f32| hash(cell + Vec3::new(x, y, z));
+	let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+	let x00 = lerp(corner(0.0, 0.0, 0.0), corner(1.0, 0.0, 0.0), t.x);
+	let x10 = lerp(corner(0.0, 1.0, 0.0), corner(1.0, 1.0, 0.0), t.x);
+	let x01 = lerp(corner(0.0, 0.0, 1.0), corner(1.0, 0.0, 1.0), t.x);
+	let x11 = lerp(corner(0.0, 1.0, 1.0), corner(1.0, 1.0, 1.0), t.x);
+	lerp(lerp(x00, x10, t.y), lerp(x01, x11, t.y), t.z)
+}
+
+/// Pseudo random value in the range [-1, 1] for a lattice point
+fn hash(p: Vec3) -> f32 {
+	let (x, y, z) = (p.x as i32 as u32, p.y as i32 as u32, p.z as i32 as u32);
+	let mut h = x.wrapping_mul(0x8da6b343) ^ y.wrapping_mul(0xd8163841) ^ z.wrapping_mul(0xcb1ab31f);
+	h ^= h >> 13;
+	h = h.wrapping_mul(0x5bd1e995);
+	h ^= h >> 15;
+	h as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
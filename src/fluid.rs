@@ -0,0 +1,158 @@
+use std::f32::consts::PI;
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Water or any other fluid that fills the [`ColliderShape`] of the entity up to its highest point,
+/// the bodies that overlap it float depending on their density and are dragged by the fluid. The
+/// buoyancy uses the gravity of each body, with its own [`Gravity`] and [`GravityScale`], as an
+/// acceleration so there is none with [`GravityEffect::None`] and [`GravityEffect::Velocity`]
+/// Required components for work: [`ColliderBundle`]
+/// Affects the entities with: [`AccumulatedForce`], [`Resistance`], [`ColliderShape`]
+#[derive(Component, Clone, Copy)]
+pub struct FluidVolume {
+	/// Mass per square unit, bodies with a lower density float
+	pub density: f32,
+	/// How fast the submerged part of a body matches the flow, an acceleration per unit of speed
+	/// like the drag of [`ForceZone::Current`]
+	pub linear_drag: f32,
+	/// How fast the rotation of the submerged part of a body stops
+	pub angular_drag: f32,
+	/// Velocity of the fluid
+	pub flow: Vec2,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn buoyancy(
+	gravity_effect: Res<GravityEffect>,
+	global_gravity: Option<Res<Gravity>>,
+	fluids: Query<(&FluidVolume, &Transform, &ColliderShape, &CollisionInfo)>,
+	mut bodies: Query<(
+		&Transform,
+		&ColliderShape,
+		&Velocity,
+		&Resistance,
+		&mut AccumulatedForce,
+		Option<&Gravity>,
+		Option<&GravityScale>,
+		Option<&Body>,
+	)>,
+) {
+	// Without an accelerating gravity there is no weight to displace
+	if matches!(*gravity_effect, GravityEffect::None | GravityEffect::Velocity) { return; }
+	for (fluid, fluid_transform, fluid_shape, info) in fluids.iter() {
+		for &entity in info.sources.iter() {
+			let Ok((transform, shape, velocity, resistance, mut accumulated, gravity, scale, body)) = bodies.get_mut(entity) else { continue };
+			let Some(g) = crate::entity_gravity(global_gravity.as_deref(), gravity, scale, body) else { continue };
+			// Without gravity there is no surface
+			let up = -g.normalize_or_zero();
+			if up == Vec2::ZERO { continue; }
+			let (_, surface) = fluid_shape.as_collider(GlobalTransform::from(*fluid_transform)).range_along_axis(up);
+			let Some((area, total_area, center)) = submerged(shape, *transform, up, surface) else { continue };
+			if area <= 0.0 { continue; }
+
+			// Archimedes, the weight of the displaced fluid pushes up from the center of buoyancy
+			let position = transform.translation.truncate();
+			accumulated.add_at_point(-g * fluid.density * area, center - position);
+
+			let fraction = area / total_area;
+			let relative = velocity.linear + velocity.angular * (center - position).perp() - fluid.flow;
+			accumulated.add_at_point(-relative * fluid.linear_drag * fraction * resistance.mass, center - position);
+			accumulated.angular -= velocity.angular * fluid.angular_drag * fraction * resistance.inertia;
+		}
+	}
+}
+
+/// Area of the shape under the surface, the total area and the centroid of the submerged part
+fn submerged(shape: &ColliderShape, transform: Transform, up: Vec2, surface: f32) -> Option<(f32, f32, Vec2)> {
+	match *shape {
+		ColliderShape::Circle(radius) => {
+			let center = transform.translation.truncate();
+			let total = PI * radius * radius;
+			// Height of the surface over the center
+			let h = (surface - center.dot(up)).clamp(-radius, radius);
+			// Circular segment over the surface
+			let cap = radius * radius * (h / radius).acos() - h * (radius * radius - h * h).sqrt();
+			let area = total - cap;
+			if area <= f32::EPSILON { return Some((0.0, total, center)); }
+			let cap_centroid = if cap > f32::EPSILON { 2.0 * (radius * radius - h * h).powf(1.5) / (3.0 * cap) } else { 0.0 };
+			Some((area, total, center - up * cap * cap_centroid / area))
+		}
+		ColliderShape::Square(..) | ColliderShape::Polygon(_) => {
+			let vertices = shape.as_collider(GlobalTransform::from(transform)).get_positions();
+			let (total, _) = area_centroid(&vertices);
+			let (area, centroid) = area_centroid(&clip_below(&vertices, up, surface));
+			Some((area, total, centroid))
+		}
+		_ => None,
+	}
+}
+
+/// Part of the polygon where `p.dot(up) <= surface`
+fn clip_below(vertices: &[Vec2], up: Vec2, surface: f32) -> Vec<Vec2> {
+	let mut clipped = Vec::with_capacity(vertices.len() + 1);
+	for (i, &p1) in vertices.iter().enumerate() {
+		let p2 = vertices[(i + 1) % vertices.len()];
+		let (d1, d2) = (p1.dot(up) - surface, p2.dot(up) - surface);
+		if d1 <= 0.0 { clipped.push(p1); }
+		if (d1 < 0.0) != (d2 < 0.0) && d1 != d2 {
+			clipped.push(p1 + (p2 - p1) * (d1 / (d1 - d2)));
+		}
+	}
+	clipped
+}
+
+/// Area and centroid of a polygon in any winding order
+fn area_centroid(vertices: &[Vec2]) -> (f32, Vec2) {
+	if vertices.len() < 3 { return (0.0, Vec2::ZERO); }
+	let (mut area, mut centroid) = (0.0, Vec2::ZERO);
+	for (i, &p1) in vertices.iter().enumerate() {
+		let p2 = vertices[(i + 1) % vertices.len()];
+		let cross = p1.perp_dot(p2);
+		area += cross;
+		centroid += (p1 + p2) * cross;
+	}
+	if area.abs() < f32::EPSILON { return (0.0, Vec2::ZERO); }
+	(area.abs() * 0.5, centroid / (3.0 * area))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn half_submerged_square() {
+		let (area, total, centroid) = submerged(&ColliderShape::Square(0.5, 0.5), Transform::default(), Vec2::Y, 0.0).unwrap();
+		assert!((area - 0.5).abs() < 1e-5);
+		assert!((total - 1.0).abs() < 1e-5);
+		assert!(centroid.abs_diff_eq(Vec2::new(0.0, -0.25), 1e-5));
+	}
+
+	#[test]
+	fn rotated_square() {
+		// Rotated 45° the submerged half is a triangle with its centroid at a third of its height
+		let transform = Transform::from_rotation(Quat::from_rotation_z(PI / 4.0));
+		let (area, _, centroid) = submerged(&ColliderShape::Square(0.5, 0.5), transform, Vec2::Y, 0.0).unwrap();
+		assert!((area - 0.5).abs() < 1e-5);
+		assert!(centroid.abs_diff_eq(Vec2::new(0.0, -(0.5_f32).sqrt() / 3.0), 1e-5));
+	}
+
+	#[test]
+	fn circle_fully_and_not_submerged() {
+		let transform = Transform::from_xyz(2.0, 3.0, 0.0);
+		let (area, total, centroid) = submerged(&ColliderShape::Circle(1.0), transform, Vec2::Y, 10.0).unwrap();
+		assert!((area - PI).abs() < 1e-4);
+		assert!((total - PI).abs() < 1e-4);
+		assert!(centroid.abs_diff_eq(Vec2::new(2.0, 3.0), 1e-4));
+
+		let (area, ..) = submerged(&ColliderShape::Circle(1.0), transform, Vec2::Y, 1.0).unwrap();
+		assert_eq!(area, 0.0);
+	}
+
+	#[test]
+	fn half_submerged_circle() {
+		// The centroid of a half disc is 4r/3π under its flat side
+		let (area, _, centroid) = submerged(&ColliderShape::Circle(1.0), Transform::default(), Vec2::Y, 0.0).unwrap();
+		assert!((area - PI / 2.0).abs() < 1e-4);
+		assert!(centroid.abs_diff_eq(Vec2::new(0.0, -4.0 / (3.0 * PI)), 1e-4));
+	}
+}
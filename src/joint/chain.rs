@@ -0,0 +1,209 @@
+use bevy::{prelude::*, ecs::system::Command, math::vec2};
+use crate::prelude::*;
+
+/// Where a [`Chain`] is attached
+#[derive(Clone, Copy)]
+pub enum ChainAnchor {
+	/// Point in the local space of an entity
+	Entity(Entity, Vec2),
+	/// Fixed point in the world
+	Point(Vec2),
+}
+
+/// How the segments of a [`Chain`] are connected
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum ChainLink {
+	/// The ends of the segments are pinned together so they rotate like the links of a chain
+	#[default]
+	Revolute,
+	/// The centers of the segments are kept at exactly the segment length
+	Distance,
+	/// The centers of the segments can get closer but never further than the segment length,
+	/// cheaper and slack like a rope
+	Rope,
+}
+
+/// Spawns a line of small bodies connected by joints between two anchors, for bridges, chains
+/// and grappling hooks, long chains need more [`joint_iterations`](crate::PhysicsPlugin::joint_iterations)
+/// to not stretch
+pub struct Chain {
+	pub start: ChainAnchor,
+	pub end: ChainAnchor,
+	pub segments: u32,
+	/// Length of each segment, if [`None`] the segments fill the distance between the anchors. A
+	/// longer chain starts hanging in a V under the anchors and a shorter one starts in a straight
+	/// line from `start` with the last joint stretched
+	pub segment_length: Option<f32>,
+	pub segment_mass: f32,
+	/// Collider of every segment, in its local space where the chain goes along the X axis
+	pub collider: Option<ColliderShape>,
+	pub link: ChainLink,
+}
+
+impl Chain {
+	pub fn new(start: ChainAnchor, end: ChainAnchor, segments: u32) -> Self {
+		Self {
+			start,
+			end,
+			segments,
+			segment_length: None,
+			segment_mass: 1.0,
+			collider: None,
+			link: default(),
+		}
+	}
+
+	pub fn with_segment_length(mut self, segment_length: f32) -> Self {
+		self.segment_length = Some(segment_length);
+		self
+	}
+
+	pub fn with_segment_mass(mut self, segment_mass: f32) -> Self {
+		self.segment_mass = segment_mass;
+		self
+	}
+
+	pub fn with_collider(mut self, collider: ColliderShape) -> Self {
+		self.collider = Some(collider);
+		self
+	}
+
+	pub fn with_link(mut self, link: ChainLink) -> Self {
+		self.link = link;
+		self
+	}
+
+	/// Returns the entities of the chain, the segments are placed between the anchors when the
+	/// commands are applied, so insert their sprites without a [`Transform`]
+	pub fn spawn(self, commands: &mut Commands) -> ChainEntities {
+		let count = self.segments.max(1);
+		let entities = ChainEntities {
+			segments: (0..count).map(|_| commands.spawn().id()).collect(),
+			joints: (0..=count).map(|_| commands.spawn().id()).collect(),
+			anchors: [self.start, self.end].iter()
+				.filter(|anchor| matches!(anchor, ChainAnchor::Point(_)))
+				.map(|_| commands.spawn().id())
+				.collect(),
+		};
+		commands.add(SpawnChain { chain: self, entities: entities.clone() });
+		entities
+	}
+}
+
+/// Entities spawned by [`Chain::spawn`]
+#[derive(Clone)]
+pub struct ChainEntities {
+	/// Segments from `start` to `end`
+	pub segments: Vec<Entity>,
+	/// Joints from `start` to `end`, one more than the segments
+	pub joints: Vec<Entity>,
+	/// Entities created for the [`ChainAnchor::Point`] anchors
+	pub anchors: Vec<Entity>,
+}
+
+impl ChainEntities {
+	/// Despawns the whole chain, the entities used as anchors are kept
+	pub fn despawn(&self, commands: &mut Commands) {
+		for &entity in self.segments.iter().chain(&self.joints).chain(&self.anchors) {
+			commands.entity(entity).despawn();
+		}
+	}
+}
+
+struct SpawnChain {
+	chain: Chain,
+	entities: ChainEntities,
+}
+
+impl Command for SpawnChain {
+	fn write(self, world: &mut World) {
+		let Self { chain, entities: ChainEntities { segments, joints, anchors } } = self;
+		let mut anchors = anchors.into_iter();
+		let (start, start_anchor, start_point) = resolve_anchor(world, chain.start, &mut anchors);
+		let (end, end_anchor, end_point) = resolve_anchor(world, chain.end, &mut anchors);
+
+		let count = segments.len() as f32;
+		let direction = (end_point - start_point).try_normalize().unwrap_or(Vec2::X);
+		let distance = start_point.distance(end_point);
+		let length = chain.segment_length.unwrap_or(distance / count);
+		// A chain longer than the distance hangs in a V so every link starts at its length
+		let total = length * count;
+		let normal = direction.perp();
+		let down = if normal.y > 0.0 { -normal } else { normal };
+		let bottom = (start_point + end_point) * 0.5 + down * (total * total - distance * distance).max(0.0).sqrt() * 0.5;
+		let point_at = |s: f32| {
+			let half = total * 0.5;
+			if total <= distance {
+				start_point + direction * s
+			} else if s <= half {
+				start_point + (bottom - start_point) * (s / half)
+			} else {
+				bottom + (end_point - bottom) * ((s - half) / half)
+			}
+		};
+
+		let resistance = match (&chain.collider, chain.link) {
+			(Some(collider), _) => Resistance::from_shape(chain.segment_mass, collider),
+			// Thin rod
+			(None, ChainLink::Revolute) => Resistance::from_shape(chain.segment_mass, &ColliderShape::Square(length * 0.5, 0.0)),
+			(None, _) => Resistance::from_shape(chain.segment_mass, &ColliderShape::Point),
+		};
+		for (i, &segment) in segments.iter().enumerate() {
+			let (from, to) = (point_at(length * i as f32), point_at(length * (i + 1) as f32));
+			let along = to - from;
+			let mut segment = world.entity_mut(segment);
+			segment
+				.insert_bundle(TransformBundle::from_transform(Transform {
+					translation: ((from + to) * 0.5).extend(0.0),
+					rotation: Quat::from_rotation_z(along.y.atan2(along.x)),
+					..default()
+				}))
+				.insert_bundle(KinematicBundle {
+					forces: ForcesBundle { resistance, ..default() },
+					..default()
+				});
+			if let Some(collider) = &chain.collider {
+				segment.insert_bundle(ColliderBundle::new(collider.clone())).insert(Body::Dynamic);
+			}
+		}
+
+		// Anchors of every link in the local space of the segments
+		let (head, tail, kind) = match chain.link {
+			ChainLink::Revolute => (vec2(-length * 0.5, 0.0), vec2(length * 0.5, 0.0), JointKind::Revolute { limits: None, motor: None }),
+			ChainLink::Distance => (Vec2::ZERO, Vec2::ZERO, JointKind::Distance { min: length, max: length }),
+			ChainLink::Rope     => (Vec2::ZERO, Vec2::ZERO, JointKind::Distance { min: 0.0, max: length }),
+		};
+		// Between an anchor and the segment next to it the distance is only half a segment
+		let end_kind = match kind {
+			JointKind::Distance { min, max } => JointKind::Distance { min: min * 0.5, max: max * 0.5 },
+			kind => kind,
+		};
+
+		let first = segments[0];
+		let last = segments[segments.len() - 1];
+		let mut joints = joints.into_iter();
+		let mut insert_joint = |joint: Joint| if let Some(entity) = joints.next() { world.entity_mut(entity).insert(joint); };
+		insert_joint(Joint::new(start, first, end_kind).with_anchors(start_anchor, head));
+		for pair in segments.windows(2) {
+			insert_joint(Joint::new(pair[0], pair[1], kind).with_anchors(tail, head));
+		}
+		insert_joint(Joint::new(last, end, end_kind).with_anchors(tail, end_anchor));
+	}
+}
+
+/// Entity and local point of the anchor and its position in the world, a point in the world uses
+/// one of the `points` entities so the joints can use it
+fn resolve_anchor(world: &mut World, anchor: ChainAnchor, points: &mut impl Iterator<Item = Entity>) -> (Entity, Vec2, Vec2) {
+	match anchor {
+		ChainAnchor::Entity(entity, local) => {
+			let transform = world.get::<Transform>(entity).copied().unwrap_or_default();
+			(entity, local, transform.mul_vec3(local.extend(0.0)).truncate())
+		}
+		ChainAnchor::Point(point) => {
+			let entity = points.next().unwrap_or_else(|| world.spawn().id());
+			world.entity_mut(entity)
+				.insert_bundle(TransformBundle::from_transform(Transform::from_translation(point.extend(0.0))));
+			(entity, Vec2::ZERO, point)
+		}
+	}
+}
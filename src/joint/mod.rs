@@ -0,0 +1,383 @@
+use std::f32::consts::PI;
+use bevy::{prelude::*, math::Mat2};
+use crate::solver::{SolverBody, effective_mass, CORRECTION};
+
+mod chain; pub use chain::*;
+mod mouse; pub use mouse::*;
+
+/// Connects two entities with [`Transform`] and [`Velocity`](crate::kinematic::Velocity), the joint
+/// lives in its own entity so despawning it disconnects the entities
+#[derive(Component)]
+pub struct Joint {
+	pub entity_a: Entity,
+	pub entity_b: Entity,
+	/// Point where the joint is attached to `entity_a`, in its local space
+	pub anchor_a: Vec2,
+	/// Point where the joint is attached to `entity_b`, in its local space
+	pub anchor_b: Vec2,
+	pub kind: JointKind,
+	/// Rotation of `entity_b` relative to `entity_a` where the angular limits and locks are
+	/// measured from, [`None`] takes the relative rotation of the entities the first time the
+	/// joint is solved so they keep the angle they had when they were connected
+	pub reference_angle: Option<f32>,
+	/// If the colliders of the connected entities can touch, `false` by default so pieces that
+	/// overlap at the anchors do not push each other
+	pub collide_connected: bool,
+}
+
+#[derive(Clone, Copy)]
+pub enum JointKind {
+	/// Keeps the distance between the anchors between `min` and `max`, if both are equal it
+	/// behaves like a rigid rod
+	Distance { min: f32, max: f32 },
+	// F = -k*x - c*v
+	/// Damped Hookean spring
+	Spring { rest_length: f32, stiffness: f32, damping: f32 },
+	/// Pin that keeps both anchors together and lets the entities rotate, the limits are the
+	/// minimum and maximum angle of `entity_b` relative to `entity_a` in the range [-π, π]
+	/// measured from the reference angle
+	Revolute { limits: Option<(f32, f32)>, motor: Option<JointMotor> },
+	/// Slider that only lets `entity_b` move along `axis` without rotating relative to `entity_a`,
+	/// the axis is in the local space of `entity_a` and the limits are the minimum and maximum
+	/// distance between the anchors along it
+	Prismatic { axis: Vec2, limits: Option<(f32, f32)>, motor: Option<JointMotor> },
+	/// Wheel of a vehicle, `entity_b` rotates freely and moves along `axis` held by a suspension
+	/// spring, the axis is in the local space of `entity_a` and the motor drives the rotation
+	Wheel { axis: Vec2, stiffness: f32, damping: f32, motor: Option<JointMotor> },
+	/// Locks the relative position and the relative rotation at the reference angle, each lock is
+	/// rigid or soft with a `(stiffness, damping)` spring
+	Weld { linear: Option<(f32, f32)>, angular: Option<(f32, f32)> },
+	/// Pulls the anchor of `entity_b` toward the anchor of `entity_a` with a spring whose force is
+	/// limited, `entity_a` is usually an entity without velocity that is moved by hand like the cursor
+	Target { stiffness: f32, damping: f32, max_force: f32 },
+}
+
+/// Removes the [`Joint`] of the entity when the force it applies is greater than this
+#[derive(Component, Clone, Copy, Deref, DerefMut)]
+pub struct BreakForce(pub f32);
+
+/// Removes the [`Joint`] of the entity when the torque it applies is greater than this
+#[derive(Component, Clone, Copy, Deref, DerefMut)]
+pub struct BreakTorque(pub f32);
+
+/// Sent when a [`Joint`] is removed by [`BreakForce`] or [`BreakTorque`]
+pub struct JointBroken {
+	/// Entity that had the joint, the entity itself is not despawned
+	pub joint: Entity,
+	pub entity_a: Entity,
+	pub entity_b: Entity,
+}
+
+/// Drives a joint toward a target velocity without exceeding a maximum force, or torque if the
+/// joint rotates
+#[derive(Clone, Copy)]
+pub struct JointMotor {
+	pub target_velocity: f32,
+	pub max_force: f32,
+}
+
+impl Joint {
+	/// Joint attached to the origin of both entities
+	pub fn new(entity_a: Entity, entity_b: Entity, kind: JointKind) -> Self {
+		Self {
+			entity_a,
+			entity_b,
+			anchor_a: Vec2::ZERO,
+			anchor_b: Vec2::ZERO,
+			kind,
+			reference_angle: None,
+			collide_connected: false,
+		}
+	}
+
+	pub fn with_anchors(mut self, anchor_a: Vec2, anchor_b: Vec2) -> Self {
+		self.anchor_a = anchor_a;
+		self.anchor_b = anchor_b;
+		self
+	}
+
+	pub fn with_reference_angle(mut self, reference_angle: f32) -> Self {
+		self.reference_angle = Some(reference_angle);
+		self
+	}
+
+	pub fn with_collisions(mut self) -> Self {
+		self.collide_connected = true;
+		self
+	}
+
+	/// Applies the springs and the motors, only once per step because they depend on the time
+	pub(crate) fn apply_forces(&self, mut a: SolverBody, mut b: SolverBody, delta: f32) -> (SolverBody, SolverBody) {
+		let pa = a.world_point(self.anchor_a);
+		let pb = b.world_point(self.anchor_b);
+		match self.kind {
+			JointKind::Distance { .. } => {}
+			JointKind::Target { stiffness, damping, max_force } => {
+				solve_target(&mut a, &mut b, pa, pb, stiffness, damping, max_force, delta);
+			}
+			JointKind::Spring { rest_length, stiffness, damping } => {
+				let Some((normal, length)) = direction(pa, pb) else { return (a, b) };
+				solve_spring(&mut a, &mut b, pa, pb, normal, length - rest_length, stiffness, damping, delta);
+			}
+			JointKind::Revolute { motor, .. } => {
+				if let Some(motor) = motor { solve_angular_motor(&mut a, &mut b, motor, delta); }
+			}
+			JointKind::Prismatic { axis, motor, .. } => {
+				let axis = Mat2::from_angle(a.rotation) * axis.normalize_or_zero();
+				if let Some(motor) = motor { solve_linear_motor(&mut a, &mut b, pb, axis, motor, delta); }
+			}
+			JointKind::Wheel { axis, stiffness, damping, motor } => {
+				let axis = Mat2::from_angle(a.rotation) * axis.normalize_or_zero();
+				let translation = (pb - pa).dot(axis);
+				if let Some(motor) = motor { solve_angular_motor(&mut a, &mut b, motor, delta); }
+				solve_spring(&mut a, &mut b, pb, pb, axis, translation, stiffness, damping, delta);
+			}
+			JointKind::Weld { linear, angular } => {
+				if let Some((stiffness, damping)) = angular {
+					let torque = -stiffness * self.relative_angle(&a, &b) - damping * (b.angular - a.angular);
+					apply_angular_impulse(&mut a, &mut b, torque * delta);
+				}
+				if let Some((stiffness, damping)) = linear {
+					let error = pb - pa;
+					for axis in [Vec2::X, Vec2::Y] {
+						solve_spring(&mut a, &mut b, pa, pb, axis, error.dot(axis), stiffness, damping, delta);
+					}
+				}
+			}
+		}
+		(a, b)
+	}
+
+	/// Solves the rigid constraints, it can run many times per step to converge in long chains
+	pub(crate) fn solve(&self, mut a: SolverBody, mut b: SolverBody) -> (SolverBody, SolverBody) {
+		let pa = a.world_point(self.anchor_a);
+		let pb = b.world_point(self.anchor_b);
+		match self.kind {
+			JointKind::Distance { min, max } => {
+				let Some((normal, length)) = direction(pa, pb) else { return (a, b) };
+				if let Some((error, bound)) = limit(length, min, max) {
+					solve_axis(&mut a, &mut b, pa, pb, normal, error, bound);
+				}
+			}
+			JointKind::Spring { .. } | JointKind::Target { .. } => {}
+			JointKind::Revolute { limits, .. } => {
+				if let Some((lower, upper)) = limits {
+					if let Some((error, bound)) = limit(self.relative_angle(&a, &b), lower, upper) {
+						solve_angle(&mut a, &mut b, error, bound);
+					}
+				}
+				solve_point(&mut a, &mut b, pa, pb);
+			}
+			JointKind::Prismatic { axis, limits, .. } => {
+				// The point of `entity_a` under the anchor of `entity_b` is used for both bodies
+				let axis = Mat2::from_angle(a.rotation) * axis.normalize_or_zero();
+				let translation = (pb - pa).dot(axis);
+				if let Some((lower, upper)) = limits {
+					if let Some((error, bound)) = limit(translation, lower, upper) {
+						solve_axis(&mut a, &mut b, pb, pb, axis, error, bound);
+					}
+				}
+				let angle = self.relative_angle(&a, &b);
+				solve_angle(&mut a, &mut b, angle, Bound::Both);
+				solve_axis(&mut a, &mut b, pb, pb, axis.perp(), (pb - pa).dot(axis.perp()), Bound::Both);
+			}
+			JointKind::Wheel { axis, .. } => {
+				let axis = Mat2::from_angle(a.rotation) * axis.normalize_or_zero();
+				solve_axis(&mut a, &mut b, pb, pb, axis.perp(), (pb - pa).dot(axis.perp()), Bound::Both);
+			}
+			JointKind::Weld { linear, angular } => {
+				if angular.is_none() {
+					let angle = self.relative_angle(&a, &b);
+					solve_angle(&mut a, &mut b, angle, Bound::Both);
+				}
+				if linear.is_none() { solve_point(&mut a, &mut b, pa, pb); }
+			}
+		}
+		(a, b)
+	}
+
+	/// Rotation of `b` relative to `a` minus the reference angle, in the range [-π, π]
+	fn relative_angle(&self, a: &SolverBody, b: &SolverBody) -> f32 {
+		let angle = (b.rotation - a.rotation - self.reference_angle.unwrap_or(0.0)) % (2.0 * PI);
+		if angle > PI { angle - 2.0 * PI } else if angle < -PI { angle + 2.0 * PI } else { angle }
+	}
+}
+
+/// Linear impulse and angular impulse around `point` that a joint applied to a body, found from
+/// the change of its momentum, [`None`] if the body can not move
+pub(crate) fn reaction(before: &SolverBody, after: &SolverBody, point: Vec2) -> Option<(Vec2, f32)> {
+	if before.inv_mass == Vec2::ZERO && before.inv_inertia == 0.0 { return None; }
+	let momentum = |velocity: f32, inv_mass: f32| if inv_mass > 0.0 { velocity / inv_mass } else { 0.0 };
+	let change = after.linear - before.linear;
+	let impulse = Vec2::new(
+		momentum(change.x, before.inv_mass.x),
+		momentum(change.y, before.inv_mass.y),
+	);
+	// Around the center of mass the linear impulse also turns the body, remove it to get the
+	// angular impulse around the anchor
+	let angular_impulse = momentum(after.angular - before.angular, before.inv_inertia)
+		- (point - before.position).perp_dot(impulse);
+	Some((impulse, angular_impulse))
+}
+
+/// Direction from `pa` to `pb` and the distance between them
+fn direction(pa: Vec2, pb: Vec2) -> Option<(Vec2, f32)> {
+	let d = pb - pa;
+	let length = d.length();
+	if length < f32::EPSILON { return None; }
+	Some((d / length, length))
+}
+
+/// Impulses a constraint is allowed to apply
+#[derive(Clone, Copy)]
+enum Bound {
+	Both,
+	/// Only pushes `entity_b` along the axis
+	Positive,
+	/// Only pulls `entity_b` against the axis
+	Negative,
+}
+
+impl Bound {
+	fn clamp(self, impulse: f32) -> f32 {
+		match self {
+			Self::Both     => impulse,
+			Self::Positive => impulse.max(0.0),
+			Self::Negative => impulse.min(0.0),
+		}
+	}
+}
+
+/// Error of `value` outside of the range and the impulses that reduce it, [`None`] inside
+fn limit(value: f32, lower: f32, upper: f32) -> Option<(f32, Bound)> {
+	if lower == upper { return Some((value - lower, Bound::Both)); }
+	if value < lower { return Some((value - lower, Bound::Positive)); }
+	if value > upper { return Some((value - upper, Bound::Negative)); }
+	None
+}
+
+/// Removes the relative velocity between `pa` and `pb` along `axis` and corrects the position `error`
+fn solve_axis(a: &mut SolverBody, b: &mut SolverBody, pa: Vec2, pb: Vec2, axis: Vec2, error: f32, bound: Bound) {
+	let mass = effective_mass(a, b, pa - a.position, pb - b.position, axis);
+	if mass == 0.0 { return; }
+
+	let speed = (b.velocity_at(pb) - a.velocity_at(pa)).dot(axis);
+	let impulse = bound.clamp(-speed * mass);
+	a.apply_impulse(-axis * impulse, pa);
+	b.apply_impulse( axis * impulse, pb);
+
+	let correction = -error * CORRECTION * mass;
+	a.apply_displacement(-axis * correction, pa);
+	b.apply_displacement( axis * correction, pb);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn solve_spring(
+	a: &mut SolverBody,
+	b: &mut SolverBody,
+	pa: Vec2,
+	pb: Vec2,
+	axis: Vec2,
+	stretch: f32,
+	stiffness: f32,
+	damping: f32,
+	delta: f32,
+) {
+	let speed = (b.velocity_at(pb) - a.velocity_at(pa)).dot(axis);
+	let force = -stiffness * stretch - damping * speed;
+	a.apply_impulse(-axis * force * delta, pa);
+	b.apply_impulse( axis * force * delta, pb);
+}
+
+fn solve_linear_motor(a: &mut SolverBody, b: &mut SolverBody, point: Vec2, axis: Vec2, motor: JointMotor, delta: f32) {
+	let mass = effective_mass(a, b, point - a.position, point - b.position, axis);
+	let speed = (b.velocity_at(point) - a.velocity_at(point)).dot(axis);
+	let max_impulse = motor.max_force * delta;
+	let impulse = ((motor.target_velocity - speed) * mass).clamp(-max_impulse, max_impulse);
+	a.apply_impulse(-axis * impulse, point);
+	b.apply_impulse( axis * impulse, point);
+}
+
+/// Inverse of the mass that resists a relative rotation
+fn angular_mass(a: &SolverBody, b: &SolverBody) -> f32 {
+	let inv_inertia = a.inv_inertia + b.inv_inertia;
+	if inv_inertia > 0.0 { inv_inertia.recip() } else { 0.0 }
+}
+
+fn apply_angular_impulse(a: &mut SolverBody, b: &mut SolverBody, impulse: f32) {
+	a.angular -= impulse * a.inv_inertia;
+	b.angular += impulse * b.inv_inertia;
+}
+
+fn solve_angular_motor(a: &mut SolverBody, b: &mut SolverBody, motor: JointMotor, delta: f32) {
+	let max_impulse = motor.max_force * delta;
+	let impulse = (motor.target_velocity - (b.angular - a.angular)) * angular_mass(a, b);
+	apply_angular_impulse(a, b, impulse.clamp(-max_impulse, max_impulse));
+}
+
+/// Removes the relative angular velocity and corrects the angle `error`
+fn solve_angle(a: &mut SolverBody, b: &mut SolverBody, error: f32, bound: Bound) {
+	let mass = angular_mass(a, b);
+	let impulse = bound.clamp(-(b.angular - a.angular) * mass);
+	apply_angular_impulse(a, b, impulse);
+
+	let correction = -error * CORRECTION * mass;
+	a.rotation -= correction * a.inv_inertia;
+	b.rotation += correction * b.inv_inertia;
+}
+
+/// Inverse of the effective mass matrix of an impulse applied at the points `ra` and `rb` relative
+/// to each body
+fn point_mass(a: &SolverBody, b: &SolverBody, ra: Vec2, rb: Vec2) -> Mat2 {
+	Mat2::from_cols(
+		Vec2::new(
+			a.inv_mass.x + b.inv_mass.x + a.inv_inertia * ra.y * ra.y + b.inv_inertia * rb.y * rb.y,
+			-a.inv_inertia * ra.x * ra.y - b.inv_inertia * rb.x * rb.y,
+		),
+		Vec2::new(
+			-a.inv_inertia * ra.x * ra.y - b.inv_inertia * rb.x * rb.y,
+			a.inv_mass.y + b.inv_mass.y + a.inv_inertia * ra.x * ra.x + b.inv_inertia * rb.x * rb.x,
+		),
+	)
+}
+
+/// Keeps the anchors `pa` and `pb` at the same point
+fn solve_point(a: &mut SolverBody, b: &mut SolverBody, pa: Vec2, pb: Vec2) {
+	let k = point_mass(a, b, pa - a.position, pb - b.position);
+	if k.determinant().abs() < f32::EPSILON { return; }
+	let mass = k.inverse();
+
+	let impulse = mass * -(b.velocity_at(pb) - a.velocity_at(pa));
+	a.apply_impulse(-impulse, pa);
+	b.apply_impulse( impulse, pb);
+
+	let correction = mass * -(pb - pa) * CORRECTION;
+	a.apply_displacement(-correction, pa);
+	b.apply_displacement( correction, pb);
+}
+
+// Soft constraint from "Soft Constraints" by Erin Catto
+/// Moves `pb` toward `pa` like a damped spring, it is solved implicitly so a high stiffness does
+/// not make it explode
+#[allow(clippy::too_many_arguments)]
+fn solve_target(
+	a: &mut SolverBody,
+	b: &mut SolverBody,
+	pa: Vec2,
+	pb: Vec2,
+	stiffness: f32,
+	damping: f32,
+	max_force: f32,
+	delta: f32,
+) {
+	let softness = delta * (damping + delta * stiffness);
+	if softness <= 0.0 { return; }
+	let gamma = softness.recip();
+	let beta = delta * stiffness * gamma;
+
+	let k = point_mass(a, b, pa - a.position, pb - b.position) + Mat2::from_diagonal(Vec2::splat(gamma));
+	let speed = b.velocity_at(pb) - a.velocity_at(pa);
+	let impulse = (k.inverse() * -(speed + (pb - pa) * beta)).clamp_length_max(max_force * delta);
+	a.apply_impulse(-impulse, pa);
+	b.apply_impulse( impulse, pb);
+}
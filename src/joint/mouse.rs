@@ -0,0 +1,91 @@
+use bevy::{prelude::*, math::Mat2, render::camera::{Camera2d, RenderTarget}};
+use crate::prelude::*;
+
+/// Lets the user grab the body under the cursor and drag it with a [`JointKind::Target`] joint
+/// while the button is held, only bodies with a [`ColliderShape`] can be grabbed
+#[derive(Clone, Copy)]
+pub struct MouseDragPlugin {
+	pub button: MouseButton,
+	pub stiffness: f32,
+	pub damping: f32,
+	pub max_force: f32,
+}
+
+impl Default for MouseDragPlugin {
+	fn default() -> Self {
+		Self {
+			button: MouseButton::Left,
+			stiffness: 1000.0,
+			damping: 50.0,
+			max_force: 50000.0,
+		}
+	}
+}
+
+impl Plugin for MouseDragPlugin {
+	fn build(&self, app: &mut App) {
+		app
+			.insert_resource(MouseDrag { settings: *self, grab: None })
+			.add_system(mouse_drag);
+	}
+}
+
+struct MouseDrag {
+	settings: MouseDragPlugin,
+	/// Entity that follows the cursor and the joint that pulls the grabbed body toward it
+	grab: Option<(Entity, Entity)>,
+}
+
+fn mouse_drag(
+	mut commands: Commands,
+	mut drag: ResMut<MouseDrag>,
+	buttons: Res<Input<MouseButton>>,
+	windows: Res<Windows>,
+	cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+	spatial: SpatialQuery,
+	bodies: Query<(&Transform, Option<&Body>), With<Velocity>>,
+) {
+	let settings = drag.settings;
+	if buttons.just_released(settings.button) {
+		if let Some((cursor, joint)) = drag.grab.take() {
+			commands.entity(cursor).despawn();
+			commands.entity(joint).despawn();
+		}
+	}
+
+	let Some(position) = cursor_position(&windows, &cameras) else { return };
+	if let Some((cursor, _)) = drag.grab {
+		commands.entity(cursor).insert(Transform::from_translation(position.extend(0.0)));
+	} else if buttons.just_pressed(settings.button) {
+		let grabbed = spatial.point(position).into_iter().find_map(|entity| match bodies.get(entity) {
+			Ok((transform, body)) if body != Some(&Body::Static) => Some((entity, transform)),
+			_ => None,
+		});
+		let Some((entity, transform)) = grabbed else { return };
+		// Grab the body by the point under the cursor
+		let anchor = Mat2::from_angle(-transform.rotation.to_scaled_axis().z) * (position - transform.translation.truncate());
+		let cursor = commands.spawn().insert(Transform::from_translation(position.extend(0.0))).id();
+		let joint = commands.spawn().insert(
+			Joint::new(cursor, entity, JointKind::Target {
+				stiffness: settings.stiffness,
+				damping: settings.damping,
+				max_force: settings.max_force,
+			})
+			.with_anchors(Vec2::ZERO, anchor)
+		).id();
+		drag.grab = Some((cursor, joint));
+	}
+}
+
+/// Position of the cursor in the world seen by the first 2d camera that has it in its window
+fn cursor_position(windows: &Windows, cameras: &Query<(&Camera, &GlobalTransform), With<Camera2d>>) -> Option<Vec2> {
+	cameras.iter().find_map(|(camera, transform)| {
+		let RenderTarget::Window(id) = &camera.target else { return None };
+		let window = windows.get(*id)?;
+		let position = window.cursor_position()?;
+		// Window -> normalized device coordinates -> world
+		let ndc = position / Vec2::new(window.width(), window.height()) * 2.0 - Vec2::ONE;
+		let ndc_to_world = transform.compute_matrix() * camera.projection_matrix.inverse();
+		Some(ndc_to_world.project_point3(ndc.extend(-1.0)).truncate())
+	})
+}
@@ -1,4 +1,8 @@
-use bevy::prelude::*;
+// The `Bundle` derive forgets its fields, the lint can not be allowed on the derived impl
+#![allow(clippy::forget_non_drop)]
+
+use std::ops::{Add, Mul};
+use bevy::{prelude::*, utils::HashMap};
 use crate::prelude::*;
 
 // v=d*t
@@ -26,6 +30,8 @@ pub struct Acceleration {
 }
 
 // F=m*a
+/// It keeps its value between frames, the forces computed by the crate like the ones of
+/// [`FollowTarget`] go to [`AccumulatedForce`] instead
 /// Required components for work: [`Acceleration`]
 #[derive(Default, Component)]
 pub struct Force {
@@ -35,9 +41,27 @@ pub struct Force {
 	pub angular: f32,
 }
 
+/// Sum of the forces added during the current step, it is applied together with [`Force`] and then
+/// cleared, add to it from systems labeled [`PhysicsSystem::Forces`]
+#[derive(Default, Component)]
+pub struct AccumulatedForce {
+	/// Affects the position
+	pub linear: Vec2,
+	/// Affects the rotation
+	pub angular: f32,
+}
+
+impl AccumulatedForce {
+	/// Adds a force applied at `point`, relative to the center of mass, it also adds the torque
+	pub fn add_at_point(&mut self, force: Vec2, point: Vec2) {
+		self.linear  += force;
+		self.angular += point.perp_dot(force);
+	}
+}
+
 /// Object resistance
 /// Required components for work: [`Force`]
-#[derive(Component)]
+#[derive(Component, Clone, Copy)]
 pub struct Resistance {
 	pub mass: f32,
 	pub inertia: f32,
@@ -52,13 +76,113 @@ impl Default for Resistance {
 	}
 }
 
+impl Resistance {
+	/// Resistance of a solid shape with the given mass, the inertia is computed around the origin
+	/// of the shape, points use a unit radius
+	pub fn from_shape(mass: f32, shape: &ColliderShape) -> Self {
+		let inertia = match shape {
+			ColliderShape::Point => mass,
+			&ColliderShape::Circle(radius) => 0.5 * mass * radius * radius,
+			&ColliderShape::Square(w, h) => mass * (w * w + h * h) / 3.0,
+			ColliderShape::AABB(size) => mass * size.length_squared() / 3.0,
+			ColliderShape::Polygon(vertices) => {
+				let (mut numerator, mut denominator) = (0.0, 0.0);
+				for (i, &p1) in vertices.iter().enumerate() {
+					let p2 = vertices[(i + 1) % vertices.len()];
+					let cross = p1.perp_dot(p2);
+					numerator += cross * (p1.dot(p1) + p1.dot(p2) + p2.dot(p2));
+					denominator += cross;
+				}
+				mass * numerator / (6.0 * denominator)
+			}
+		};
+		Self { mass, inertia }
+	}
+}
+
 // |u|^2 * c * û * -1
 /// Enviroment resistance
 /// Required components for work: [`Force`], [`Velocity`]
-#[derive(Default, Component)]
+#[derive(Default, Component, Clone, Copy)]
 pub struct Drag {
 	pub linear: f32,
 	pub angular: f32,
+	pub model: DragModel,
+}
+
+/// How the [`Drag`] grows with the speed
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum DragModel {
+	/// `|u|^2 * c`, resistance of the air at high speeds
+	#[default]
+	Quadratic,
+	/// `|u| * c`, resistance of a viscous fluid at low speeds
+	Linear,
+}
+
+impl Drag {
+	fn magnitude(&self, coefficient: f32, speed: f32) -> f32 {
+		match self.model {
+			DragModel::Quadratic => coefficient * speed * speed,
+			DragModel::Linear    => coefficient * speed,
+		}
+	}
+
+	/// Force that opposes the linear velocity
+	pub fn linear_force(&self, linear: Vec2) -> Vec2 {
+		-linear.normalize_or_zero() * self.magnitude(self.linear, linear.length())
+	}
+
+	/// Torque that opposes the angular velocity
+	pub fn angular_force(&self, angular: f32) -> f32 {
+		-angular.signum() * self.magnitude(self.angular, angular.abs())
+	}
+}
+
+// v = v0 * e^(-c*t)
+/// Exponential decay of the velocity that does not depend on the mass, useful to slow down
+/// entities smoothly in top-down games
+/// Required components for work: [`Velocity`]
+#[derive(Default, Component, Clone, Copy)]
+pub struct Damping {
+	pub linear: f32,
+	pub angular: f32,
+}
+
+/// Speed caps applied after the velocity is integrated, the distance moved in the step is capped too
+/// Required components for work: [`Velocity`]
+#[derive(Component, Clone, Copy)]
+pub struct VelocityLimits {
+	/// Maximum length of the linear velocity
+	pub max_linear: f32,
+	/// Maximum absolute angular velocity
+	pub max_angular: f32,
+	/// Lowest value of each axis of the linear velocity, in a platformer `-min_axis.y` is the
+	/// maximum fall speed
+	pub min_axis: Vec2,
+	/// Highest value of each axis of the linear velocity
+	pub max_axis: Vec2,
+}
+
+impl Default for VelocityLimits {
+	fn default() -> Self {
+		Self {
+			max_linear: f32::INFINITY,
+			max_angular: f32::INFINITY,
+			min_axis: Vec2::splat(f32::NEG_INFINITY),
+			max_axis: Vec2::splat(f32::INFINITY),
+		}
+	}
+}
+
+impl VelocityLimits {
+	/// Clamps the linear and angular velocity to the limits
+	pub fn clamp(&self, linear: Vec2, angular: f32) -> (Vec2, f32) {
+		(
+			linear.clamp_length_max(self.max_linear).clamp(self.min_axis, self.max_axis),
+			angular.clamp(-self.max_angular, self.max_angular),
+		)
+	}
 }
 
 #[derive(Default, Bundle)]
@@ -70,6 +194,7 @@ pub struct DynamicBundle {
 #[derive(Default, Bundle)]
 pub struct ForcesBundle {
 	pub force: Force,
+	pub accumulated: AccumulatedForce,
 	pub resistance: Resistance,
 	pub drag: Drag,
 }
@@ -80,56 +205,271 @@ pub struct KinematicBundle {
 	#[bundle] pub forces: ForcesBundle,
 }
 
+/// Prevents an entity from moving along an axis or from rotating, both the integration and the
+/// contacts respect it
+#[derive(Default, Component, Clone, Copy)]
+pub struct LockedAxes {
+	pub translation_x: bool,
+	pub translation_y: bool,
+	pub rotation: bool,
+}
+
+impl LockedAxes {
+	/// Only the rotation is locked, for example a top-down character
+	pub const ROTATION: Self = Self { translation_x: false, translation_y: false, rotation: true };
+
+	/// Multiplier that sets to zero the locked axes of a linear quantity
+	pub fn linear_mask(&self) -> Vec2 {
+		Vec2::new(
+			if self.translation_x { 0.0 } else { 1.0 },
+			if self.translation_y { 0.0 } else { 1.0 },
+		)
+	}
+
+	/// Multiplier that sets to zero an angular quantity if the rotation is locked
+	pub fn angular_mask(&self) -> f32 {
+		if self.rotation { 0.0 } else { 1.0 }
+	}
+}
+
+/// Numerical method used to advance the velocity and the position of the entities, it can be set for
+/// every entity in [`PhysicsPlugin`] and overridden per entity by inserting it as a component.
+/// The [`Attractor`](crate::field::Attractor) fields and the [`Drag`] are evaluated again inside the
+/// step, the forces added to [`AccumulatedForce`] stay constant during it and the gravity is
+/// integrated exactly apart
+#[derive(Default, Component, Clone, Copy, PartialEq)]
+pub enum Integrator {
+	/// Updates the velocity and then moves with the new velocity, cheap and stable enough for most games
+	#[default]
+	SemiImplicitEuler,
+	/// Second order, uses the average of the acceleration at the start and at the predicted end of
+	/// the step, orbits keep their energy better than with `SemiImplicitEuler`
+	VelocityVerlet,
+	/// Classic fourth order Runge-Kutta over the position and the velocity, the most precise and
+	/// also the most expensive
+	RungeKutta4,
+}
+
+impl Integrator {
+	/// Advances the state during `delta` seconds, `acceleration` is evaluated from the displacement
+	/// since the start of the step and the velocity so fields and drag are taken into account,
+	/// returns the displacement and the new velocity
+	pub fn step<T>(&self, velocity: T, delta: f32, acceleration: impl Fn(T, T) -> T) -> (T, T)
+	where T: Copy + Add<Output = T> + Mul<f32, Output = T> {
+		let start = velocity * 0.0;
+		match self {
+			Self::SemiImplicitEuler => {
+				let velocity = velocity + acceleration(start, velocity) * delta;
+				(velocity * delta, velocity)
+			}
+			Self::VelocityVerlet => {
+				let a0 = acceleration(start, velocity);
+				let displacement = velocity * delta + a0 * (0.5 * delta * delta);
+				let a1 = acceleration(displacement, velocity + a0 * delta);
+				(displacement, velocity + (a0 + a1) * (0.5 * delta))
+			}
+			Self::RungeKutta4 => {
+				let half = 0.5 * delta;
+				let v1 = velocity;
+				let a1 = acceleration(start, v1);
+				let v2 = velocity + a1 * half;
+				let a2 = acceleration(v1 * half, v2);
+				let v3 = velocity + a2 * half;
+				let a3 = acceleration(v2 * half, v3);
+				let v4 = velocity + a3 * delta;
+				let a4 = acceleration(v3 * delta, v4);
+				let sixth = delta / 6.0;
+				(
+					(v1 + (v2 + v3) * 2.0 + v4) * sixth,
+					velocity + (a1 + (a2 + a3) * 2.0 + a4) * sixth,
+				)
+			}
+		}
+	}
+}
+
+/// Fraction the pull of the fields on a sleeping body can change before it wakes up, the contacts
+/// still nudge sleeping bodies a little
+const FIELD_WAKE_CHANGE: f32 = 0.01;
+
+#[allow(clippy::type_complexity)]
 pub(crate) fn update_transform (
-	time: Res<Time>,
+	time: Res<PhysicsTime>,
+	default_integrator: Res<Integrator>,
+	gravity_effect: Res<GravityEffect>,
+	global_gravity: Option<Res<Gravity>>,
+	field: Res<crate::field::AttractorField>,
+	// Pull of the fields on every sleeping body when it fell asleep
+	mut resting: Local<HashMap<Entity, Vec2>>,
 	mut query: Query<(
+		Entity,
 		&mut Transform,
 		// Dynamics
 		&mut Velocity,
 		&mut Acceleration,
 		// Forces
-		Option<(&mut Force, &Resistance, &Drag)>,
+		Option<(&Force, &Resistance, &Drag)>,
+		Option<&mut AccumulatedForce>,
+		// Gravity
+		Option<&Gravity>,
+		Option<&GravityScale>,
 		// RigidBody
 		Option<&Body>,
-		Option<&IsSleep>,
+		Option<&mut IsSleep>,
+		Option<&Integrator>,
+		Option<&Damping>,
+		Option<&VelocityLimits>,
+		Option<&LockedAxes>,
 	)>
 ) {
+	resting.retain(|&entity, _| query.get(entity).is_ok());
 	for (
+		entity,
 		mut transform,
 		mut velocity,
 		mut acceleration,
 		forces,
+		accumulated,
+		gravity,
+		gravity_scale,
 		body,
 		is_sleep,
+		integrator,
+		damping,
+		limits,
+		locked,
 	) in query.iter_mut() {
-		if let Some(body) = body { if *body == Body::Static { return; } }
-		if let Some(is_sleep) = is_sleep { if **is_sleep { return; } }
+		// Taken before anything else so it does not pile up while the entity does not move
+		let accumulated = accumulated.map(|mut a| std::mem::take(&mut *a)).unwrap_or_default();
+		if let Some(body) = body { if *body == Body::Static { continue; } }
+		let position = transform.translation.truncate();
+		if let Some(mut is_sleep) = is_sleep {
+			// Any generated force wakes the body up, a field only when it changes like when an
+			// attractor moves, so a body can sleep while resting in a field as it does with gravity
+			if **is_sleep {
+				let pull = if forces.is_some() { field.acceleration(entity, position) } else { Vec2::ZERO };
+				let rest_pull = *resting.entry(entity).or_insert(pull);
+				let changed = pull.distance(rest_pull) > pull.length().max(rest_pull.length()) * FIELD_WAKE_CHANGE;
+				if accumulated.linear != Vec2::ZERO || accumulated.angular != 0.0 || changed {
+					**is_sleep = false;
+				}
+			}
+			if **is_sleep { continue; }
+			resting.remove(&entity);
+		}
 
 		let delta = time.delta_seconds();
-		if let Some((mut force, resistance, drag)) = forces {
-			// Drag -> Force
-			// Linear
-			let linear_speed_speed = velocity.linear.length_squared();
-			let linear_drag_magnitude = drag.linear * linear_speed_speed;
-			let linear_drag = -velocity.linear.normalize_or_zero() * linear_drag_magnitude;
-			force.linear  += linear_drag;
-			// Angular
-			let angular_speed_speed = velocity.angular * velocity.angular;
-			let angular_drag_magnitude = drag.angular * angular_speed_speed;
-			let angular_drag = -velocity.angular * angular_drag_magnitude;
-			force.angular += angular_drag;
-			// Force -> Acceleration
-			acceleration.linear  = force.linear  / resistance.mass   ;
-			acceleration.angular = force.angular / resistance.inertia;
+		let integrator = integrator.unwrap_or(&default_integrator);
+		// Damping, exact so it does not depend on the frame rate
+		if let Some(damping) = damping {
+			velocity.linear  *= (-damping.linear  * delta).exp();
+			velocity.angular *= (-damping.angular * delta).exp();
 		}
-		// Acceleration -> Velocity
-		velocity.linear  += acceleration.linear  * delta;
-		velocity.angular += acceleration.angular * delta;
-		// Stop when the change is near to zero
-		if let Some(is_sleep) = is_sleep { if **is_sleep { return; } }
+		// Acceleration of the entity for a given velocity, the fields are added apart like the gravity
+		let linear_acceleration = |linear: Vec2| match forces {
+			Some((force, resistance, drag)) => (force.linear + accumulated.linear + drag.linear_force(linear)) / resistance.mass,
+			None => acceleration.linear,
+		};
+		let field_acceleration = |displacement: Vec2| match forces {
+			Some(_) => field.acceleration(entity, position + displacement),
+			None => Vec2::ZERO,
+		};
+		let angular_acceleration = |_, angular: f32| match forces {
+			Some((force, resistance, drag)) => (force.angular + accumulated.angular + drag.angular_force(angular)) / resistance.inertia,
+			None => acceleration.angular,
+		};
+		let current_acceleration = (
+			linear_acceleration(velocity.linear),
+			angular_acceleration(0.0, velocity.angular),
+		);
+		// Acceleration -> Velocity -> Displacement
+		let (mut displacement, mut linear) = integrator.step(velocity.linear, delta, |displacement, linear| {
+			linear_acceleration(linear) + field_acceleration(displacement)
+		});
+		let (mut rotation, mut angular) = integrator.step(velocity.angular, delta, angular_acceleration);
+		// Gravity, integrated exactly so it does not depend on the frame rate
+		let g = crate::entity_gravity(global_gravity.as_deref(), gravity, gravity_scale, body).unwrap_or_default();
+		displacement += gravity_effect.apply(g, &mut linear, delta);
+
+		if forces.is_some() {
+			(acceleration.linear, acceleration.angular) = current_acceleration;
+		}
+		if let Some(limits) = limits {
+			(linear, angular) = limits.clamp(linear, angular);
+			// The displacement is the average velocity of the step, capped the same way
+			if delta > 0.0 {
+				let (average, spin) = limits.clamp(displacement / delta, rotation / delta);
+				(displacement, rotation) = (average * delta, spin * delta);
+			}
+		}
+		(velocity.linear, velocity.angular) = (linear, angular);
+		let (linear_mask, angular_mask) = locked.map_or((Vec2::ONE, 1.0), |l| (l.linear_mask(), l.angular_mask()));
+		velocity.linear  *= linear_mask;
+		velocity.angular *= angular_mask;
 		// Velocity -> Transform
-		transform.translation += velocity.linear.extend(0.0) * delta;
-		transform.rotate(Quat::from_rotation_z(velocity.angular * delta));
+		transform.translation += (displacement * linear_mask).extend(0.0);
+		transform.rotate(Quat::from_rotation_z(rotation * angular_mask));
 	}
 }
 
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn constant_acceleration_is_exact() {
+		let (velocity, acceleration, delta) = (Vec2::new(3.0, 4.0), Vec2::new(-1.0, -9.8), 0.5);
+		for integrator in [Integrator::VelocityVerlet, Integrator::RungeKutta4] {
+			let (displacement, end) = integrator.step(velocity, delta, |_, _| acceleration);
+			assert!(displacement.abs_diff_eq(velocity * delta + 0.5 * acceleration * delta * delta, 1e-5));
+			assert!(end.abs_diff_eq(velocity + acceleration * delta, 1e-5));
+		}
+	}
+
+	#[test]
+	fn runge_kutta_keeps_the_energy_of_a_spring() {
+		let stiffness = 4.0;
+		let energy = |position: f32, velocity: f32| 0.5 * velocity * velocity + 0.5 * stiffness * position * position;
+		// Largest energy error of a spring starting at rest one unit away from its rest length
+		let drift = |integrator: Integrator| {
+			let (mut position, mut velocity) = (1.0, 0.0);
+			let start = energy(position, velocity);
+			let mut drift: f32 = 0.0;
+			for _ in 0..200 {
+				let (displacement, end) = integrator.step(velocity, 0.05, |displacement, _| -stiffness * (position + displacement));
+				position += displacement;
+				velocity = end;
+				drift = drift.max((energy(position, velocity) - start).abs());
+			}
+			drift
+		};
+		assert!(drift(Integrator::RungeKutta4) < drift(Integrator::SemiImplicitEuler));
+		assert!(drift(Integrator::VelocityVerlet) < drift(Integrator::SemiImplicitEuler));
+	}
+
+	#[test]
+	fn fall_cap_limits_the_displacement() {
+		let (delta, max_fall) = (0.1, 100.0);
+		let mut time = PhysicsTime::new(1);
+		time.delta = delta;
+		let mut world = World::new();
+		world.insert_resource(time);
+		world.insert_resource(Integrator::default());
+		world.insert_resource(GravityEffect::Acceleration);
+		world.insert_resource(Gravity(Vec2::new(0.0, -1000.0)));
+		world.init_resource::<crate::field::AttractorField>();
+		let body = world.spawn()
+			.insert_bundle((Transform::default(), Velocity::default(), Acceleration::default()))
+			.insert(VelocityLimits { min_axis: Vec2::new(f32::NEG_INFINITY, -max_fall), ..default() })
+			.id();
+		let mut stage = SystemStage::single(update_transform);
+
+		for _ in 0..20 {
+			let before = world.get::<Transform>(body).unwrap().translation.y;
+			stage.run(&mut world);
+			let after = world.get::<Transform>(body).unwrap().translation.y;
+			assert!(before - after <= max_fall * delta + 1e-4, "fell {} in one step", before - after);
+			assert!(world.get::<Velocity>(body).unwrap().linear.y >= -max_fall);
+		}
+	}
+}
@@ -1,76 +1,227 @@
 pub mod kinematic;
 pub mod collision;
 pub mod rigid_body;
+pub mod solver;
+pub mod joint;
+pub mod controller;
+pub mod field;
+pub mod fluid;
+pub mod aerodynamics;
+pub mod nbody;
+pub mod trajectory;
+pub mod ballistics;
+pub mod explosion;
 
 pub mod prelude {
 	pub use crate::kinematic::*;
 	pub use crate::collision::*;
 	pub use crate::rigid_body::*;
+	pub use crate::solver::*;
+	pub use crate::joint::*;
+	pub use crate::controller::*;
+	pub use crate::field::*;
+	pub use crate::fluid::*;
+	pub use crate::aerodynamics::*;
+	pub use crate::nbody::*;
+	pub use crate::trajectory::*;
+	pub use crate::ballistics::*;
+	pub use crate::explosion::*;
 	pub use crate::PhysicsPlugin;
+	pub use crate::PhysicsStage;
+	pub use crate::PhysicsSystem;
+	pub use crate::PhysicsTime;
 	pub use crate::GravityEffect;
 	pub use crate::DebugPlugin;
 	pub use crate::Gravity;
+	pub use crate::GravityScale;
 }
 
-use bevy::{prelude::*, math::vec2};
+use bevy::{prelude::*, math::vec2, ecs::schedule::ShouldRun};
 
-#[derive(Default)]
+/// How the [`Gravity`] is interpreted, every mode is integrated with the frame delta time so it
+/// behaves the same at any frame rate
+#[derive(Default, Clone, Copy, PartialEq)]
 pub enum GravityEffect {
+	/// Gravity is ignored
 	#[default]
 	None,
+	/// Gravity is a constant fall speed in units/s, it moves the entity without changing its [`Velocity`](kinematic::Velocity).
+	/// The contact solver does not see this motion, so rigid bodies resting on something get no
+	/// friction nor [`SurfaceVelocity`](solver::SurfaceVelocity) and fall asleep on the first touch,
+	/// use `Acceleration` for rigid bodies
 	Velocity,
+	/// Gravity is an acceleration in units/s² that accumulates in the [`Velocity`](kinematic::Velocity)
 	Acceleration,
+	/// Same as `Acceleration` but the speed along the gravity direction stops growing once it reaches
+	/// the given terminal velocity in units/s
+	TerminalVelocity(f32),
+}
+
+impl GravityEffect {
+	/// Applies the gravity `g` to `velocity` during `delta` seconds, returns the displacement caused
+	/// by the gravity on top of the one caused by the velocity
+	pub(crate) fn apply(&self, g: Vec2, velocity: &mut Vec2, delta: f32) -> Vec2 {
+		match *self {
+			Self::None => Vec2::ZERO,
+			Self::Velocity => g * delta,
+			Self::Acceleration => {
+				*velocity += g * delta;
+				0.5 * g * delta * delta
+			}
+			Self::TerminalVelocity(terminal) => {
+				let magnitude = g.length();
+				if magnitude < f32::EPSILON { return Vec2::ZERO; }
+				let speed = velocity.dot(g / magnitude);
+				// Time accelerating before reaching the terminal velocity
+				let t = ((terminal - speed) / magnitude).clamp(0.0, delta);
+				*velocity += g * t;
+				0.5 * g * t * t + g * t * (delta - t)
+			}
+		}
+	}
 }
 
-#[derive(Default)]
 pub struct PhysicsPlugin {
 	pub gravity_effect: GravityEffect,
+	/// Integrator used by the entities without their own [`Integrator`](kinematic::Integrator) component
+	pub integrator: kinematic::Integrator,
+	/// Number of times every frame is simulated, each substep advances a fraction of the frame
+	/// so more substeps are slower but more stable
+	pub substeps: u32,
+	/// Number of times the joints are solved every substep, long chains of joints need more
+	/// iterations to not stretch
+	pub joint_iterations: u32,
+}
+
+impl Default for PhysicsPlugin {
+	fn default() -> Self {
+		Self {
+			gravity_effect: default(),
+			integrator: default(),
+			substeps: 1,
+			joint_iterations: 4,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub enum PhysicsStage {
+	/// Runs once per frame before the simulation
+	Prepare,
+	/// Runs once per substep, see [`PhysicsSystem`] for the order of the systems inside
+	Step,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+pub enum PhysicsSystem {
+	/// Adds forces to the [`AccumulatedForce`](kinematic::AccumulatedForce) of the entities
+	Forces,
+	/// Moves the entities
+	Integrate,
+	/// Finds the contacts between rigid bodies
+	Detect,
+	/// Solves the joints and separates the rigid bodies in contact
+	Resolve,
 }
 
 impl Plugin for PhysicsPlugin {
 	fn build(&self, app: &mut App) {
-		let mut post_update = SystemSet::new().with_system(kinematic::update_transform);
-		post_update = match self.gravity_effect {
-			GravityEffect::None         => post_update,
-			GravityEffect::Velocity     => post_update.with_system(gravity_velocity    .before(kinematic::update_transform)),
-			GravityEffect::Acceleration => post_update.with_system(gravity_acceleration.before(kinematic::update_transform))
-		};
+		let step = SystemSet::new()
+			.with_system(controller::follow_target.label(PhysicsSystem::Forces))
+			.with_system(controller::apply_thrusters.label(PhysicsSystem::Forces))
+			.with_system(field::attract.label(PhysicsSystem::Forces))
+			.with_system(field::apply_zones.label(PhysicsSystem::Forces))
+			.with_system(fluid::buoyancy.label(PhysicsSystem::Forces))
+			.with_system(aerodynamics::aerodynamics.label(PhysicsSystem::Forces))
+			.with_system(kinematic::update_transform.label(PhysicsSystem::Integrate).after(PhysicsSystem::Forces))
+			.with_system(solver::pass_through.before(PhysicsSystem::Detect))
+			.with_system(solver::detect_contacts.label(PhysicsSystem::Detect).after(PhysicsSystem::Integrate))
+			.with_system(solver::resolve.label(PhysicsSystem::Resolve).after(PhysicsSystem::Detect));
 
 		let pre_update = SystemSet::new()
 			.with_system(collision::collision_info)
 			.with_system(rigid_body::is_sleep);
 
 		app
+			.insert_resource(self.gravity_effect)
+			.insert_resource(self.integrator)
+			.insert_resource(PhysicsTime::new(self.substeps))
+			.insert_resource(solver::JointIterations(self.joint_iterations))
+			.init_resource::<solver::Contacts>()
+			.init_resource::<field::AttractorField>()
+			.init_resource::<solver::OneWayContacts>()
+			.add_event::<joint::JointBroken>()
+			.add_stage_before(CoreStage::PostUpdate, PhysicsStage::Prepare, SystemStage::parallel())
+			.add_stage_after(PhysicsStage::Prepare, PhysicsStage::Step, SystemStage::parallel().with_run_criteria(run_substep))
+			.add_system_to_stage(PhysicsStage::Prepare, prepare_substeps)
 			.add_system_set_to_stage(CoreStage::PreUpdate, pre_update)
-			.add_system_set_to_stage(CoreStage::PostUpdate,	post_update);
+			.add_system_set_to_stage(PhysicsStage::Step, step);
 	}
 }
 
-#[derive(Deref, DerefMut)]
-pub struct Gravity(pub Vec2);
+/// Time simulated by every physics step, use it instead of [`Time`] in the systems added to
+/// [`PhysicsStage::Step`]
+pub struct PhysicsTime {
+	/// Number of steps simulated every frame
+	pub substeps: u32,
+	delta: f32,
+	remaining: u32,
+}
 
-fn gravity_acceleration(
-	gravity: Option<Res<Gravity>>,
-	mut query: Query<&mut kinematic::Acceleration>,
-) {
-	if let Some(g) = gravity {
-		for mut acceleration in query.iter_mut() {
-			acceleration.linear += **g;
+impl PhysicsTime {
+	fn new(substeps: u32) -> Self {
+		Self {
+			substeps,
+			delta: 0.0,
+			remaining: 0,
 		}
 	}
+
+	/// Seconds advanced by the current step
+	pub fn delta_seconds(&self) -> f32 {
+		self.delta
+	}
 }
 
-fn gravity_velocity(
-	gravity: Option<Res<Gravity>>,
-	mut query: Query<&mut kinematic::Velocity>
-) {
-	if let Some(g) = gravity {
-		for mut velocity in query.iter_mut() {
-			velocity.linear += **g;
-		}
+fn prepare_substeps(time: Res<Time>, mut physics_time: ResMut<PhysicsTime>) {
+	let substeps = physics_time.substeps.max(1);
+	physics_time.delta = time.delta_seconds() / substeps as f32;
+	physics_time.remaining = substeps;
+}
+
+fn run_substep(mut physics_time: ResMut<PhysicsTime>) -> ShouldRun {
+	if physics_time.remaining == 0 { return ShouldRun::No; }
+	physics_time.remaining -= 1;
+	ShouldRun::YesAndCheckAgain
+}
+
+/// Gravity applied to every entity, as a resource it is the global gravity and as a component
+/// it overrides the global gravity for that entity only
+#[derive(Component, Clone, Copy, Deref, DerefMut)]
+pub struct Gravity(pub Vec2);
+
+/// Multiplies the gravity that affects the entity, `0.0` disables it and negative values invert it
+#[derive(Component, Clone, Copy, Deref, DerefMut)]
+pub struct GravityScale(pub f32);
+
+impl Default for GravityScale {
+	fn default() -> Self {
+		Self(1.0)
 	}
 }
 
+/// Gravity that affects a single entity, [`None`] if there is no gravity or the body is static
+pub(crate) fn entity_gravity(
+	global: Option<&Gravity>,
+	local: Option<&Gravity>,
+	scale: Option<&GravityScale>,
+	body: Option<&rigid_body::Body>,
+) -> Option<Vec2> {
+	if let Some(rigid_body::Body::Static) = body { return None; }
+	let gravity = local.or(global)?;
+	Some(**gravity * scale.map_or(1.0, |s| **s))
+}
+
 // Debug Plugin
 
 use bevy_prototype_lyon::prelude::*;
@@ -152,6 +303,7 @@ fn spawn_debug_shape(
 	}
 }
 
+#[allow(clippy::type_complexity)]
 fn update_debug_shape(
 	mut child_query: Query<&mut Path, With<ColliderDebugParent>>,
 	parent_query: Query<(&ColliderShape, &ColliderDebugChild) , (Without<ColliderDebugParent>, Changed<ColliderShape>)>,
@@ -207,17 +359,57 @@ fn update_debug_shape(
 fn collider_debug_transform_sync(
 	mut commands: Commands,
 	mut child_query: Query<(Entity, &mut Transform, &ColliderDebugParent)>,
-	parent_query: Query<(&Transform, &ColliderShape) , (With<ColliderDebugChild>, Without<ColliderDebugParent>)>,
+	parent_query: Query<&Transform, (With<ColliderDebugChild>, Without<ColliderDebugParent>)>,
 ) {
 	for (child, mut child_transform, ColliderDebugParent(parent)) in child_query.iter_mut() {
-		if let Ok((parent_transform, collider_shape)) = parent_query.get(*parent) {
-			match collider_shape {
-				_ => *child_transform = *parent_transform
-			}
-			
+		if let Ok(parent_transform) = parent_query.get(*parent) {
+			*child_transform = *parent_transform;
 		} else {
 			commands.entity(child).despawn();
 		}
 	}
 }
 
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Velocity and displacement after `steps` steps of `delta` seconds, the velocity moves the
+	/// entity before the gravity is applied like in the integrator
+	fn fall(effect: GravityEffect, velocity: Vec2, steps: u32, delta: f32) -> (Vec2, Vec2) {
+		let g = vec2(3.0, -10.0);
+		let (mut velocity, mut displacement) = (velocity, Vec2::ZERO);
+		for _ in 0..steps {
+			displacement += velocity * delta;
+			displacement += effect.apply(g, &mut velocity, delta);
+		}
+		(velocity, displacement)
+	}
+
+	#[test]
+	fn gravity_does_not_depend_on_the_frame_rate() {
+		let effects = [
+			GravityEffect::None,
+			GravityEffect::Velocity,
+			GravityEffect::Acceleration,
+			GravityEffect::TerminalVelocity(20.0),
+			// Reached inside the first step of 1/30 s
+			GravityEffect::TerminalVelocity(1.2),
+		];
+		for effect in effects {
+			let velocity = vec2(0.5, -1.0);
+			let (slow_velocity, slow_displacement) = fall(effect, velocity, 1, 1.0 / 30.0);
+			let (fast_velocity, fast_displacement) = fall(effect, velocity, 4, 1.0 / 120.0);
+			assert!(slow_velocity.abs_diff_eq(fast_velocity, 1e-5), "{slow_velocity} != {fast_velocity}");
+			assert!(slow_displacement.abs_diff_eq(fast_displacement, 1e-5), "{slow_displacement} != {fast_displacement}");
+		}
+	}
+
+	#[test]
+	fn terminal_velocity_stops_the_speed_along_the_gravity() {
+		let g = vec2(3.0, -10.0);
+		let (velocity, _) = fall(GravityEffect::TerminalVelocity(1.2), vec2(0.5, -1.0), 1, 1.0 / 30.0);
+		assert!((velocity.dot(g.normalize()) - 1.2).abs() < 1e-5);
+	}
+}
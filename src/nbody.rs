@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Makes every body with [`Resistance`] attract every other with Newtonian gravity, configure it by
+/// inserting the [`NBodyGravity`] resource, it must be added after [`PhysicsPlugin`]
+pub struct NBodyPlugin;
+
+impl Plugin for NBodyPlugin {
+	fn build(&self, app: &mut App) {
+		app
+			.init_resource::<NBodyGravity>()
+			.add_system_to_stage(
+				PhysicsStage::Step,
+				mutual_gravity.label(PhysicsSystem::Forces).before(PhysicsSystem::Integrate),
+			);
+	}
+}
+
+// F = G * m1 * m2 / (d^2 + e^2)
+/// Settings of the [`NBodyPlugin`]. The forces are computed once per step from the positions at its
+/// start and added to the [`AccumulatedForce`], so they stay constant during the step and
+/// [`Integrator::VelocityVerlet`] or [`Integrator::RungeKutta4`] do not make the orbits more
+/// accurate, use more substeps instead
+#[derive(Clone, Copy)]
+pub struct NBodyGravity {
+	/// Gravitational constant
+	pub g: f32,
+	/// Distance added to every pair so the force does not explode when two bodies get very close
+	pub softening: f32,
+	/// Accuracy of the Barnes–Hut approximation, a group of bodies whose size divided by its
+	/// distance is less than this pulls like a single body, `0.0` computes every pair exactly
+	pub theta: f32,
+}
+
+impl Default for NBodyGravity {
+	fn default() -> Self {
+		Self {
+			g: 1.0,
+			softening: 5.0,
+			theta: 0.5,
+		}
+	}
+}
+
+fn mutual_gravity(
+	settings: Res<NBodyGravity>,
+	mut query: Query<(&Transform, &Resistance, Option<&mut AccumulatedForce>, Option<&Body>)>,
+) {
+	let bodies: Vec<_> = query.iter()
+		.map(|(transform, resistance, ..)| (transform.translation.truncate(), resistance.mass))
+		.collect();
+	let Some(tree) = QuadTree::new(&bodies) else { return };
+	let softening = settings.softening * settings.softening;
+	for (transform, resistance, accumulated, body) in query.iter_mut() {
+		let Some(mut accumulated) = accumulated else { continue };
+		if body == Some(&Body::Static) { continue; }
+		let acceleration = tree.acceleration(transform.translation.truncate(), settings.theta, softening);
+		accumulated.linear += acceleration * settings.g * resistance.mass;
+	}
+}
+
+/// Deeper nodes are not split, so bodies at the same position do not split forever
+const MAX_DEPTH: u32 = 16;
+
+/// Barnes–Hut tree, every node stores the total mass and the center of mass of the bodies inside
+struct QuadTree {
+	mass: f32,
+	center_of_mass: Vec2,
+	/// Width of the square covered by the node
+	size: f32,
+	children: Vec<QuadTree>,
+}
+
+impl QuadTree {
+	fn new(bodies: &[(Vec2, f32)]) -> Option<Self> {
+		if bodies.is_empty() { return None; }
+		let (min, max) = bodies.iter().fold(
+			(Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+			|(min, max), &(position, _)| (min.min(position), max.max(position)),
+		);
+		let size = (max - min).max_element().max(1.0);
+		Some(Self::build(bodies, (min + max) * 0.5, size, 0))
+	}
+
+	fn build(bodies: &[(Vec2, f32)], center: Vec2, size: f32, depth: u32) -> Self {
+		let mass: f32 = bodies.iter().map(|&(_, mass)| mass).sum();
+		let center_of_mass = if mass > 0.0 {
+			bodies.iter().fold(Vec2::ZERO, |sum, &(position, mass)| sum + position * mass) / mass
+		} else {
+			center
+		};
+		let mut children = vec![];
+		if bodies.len() > 1 && depth < MAX_DEPTH {
+			let mut quadrants: [Vec<(Vec2, f32)>; 4] = default();
+			for &(position, mass) in bodies {
+				let index = (position.x >= center.x) as usize + 2 * (position.y >= center.y) as usize;
+				quadrants[index].push((position, mass));
+			}
+			let quarter = size * 0.25;
+			for (index, quadrant) in quadrants.iter().enumerate() {
+				if quadrant.is_empty() { continue; }
+				let offset = Vec2::new(
+					if index & 1 == 1 { quarter } else { -quarter },
+					if index & 2 == 2 { quarter } else { -quarter },
+				);
+				children.push(Self::build(quadrant, center + offset, size * 0.5, depth + 1));
+			}
+		}
+		Self { mass, center_of_mass, size, children }
+	}
+
+	/// Acceleration at `point` divided by the gravitational constant, `softening` is squared
+	fn acceleration(&self, point: Vec2, theta: f32, softening: f32) -> Vec2 {
+		let offset = self.center_of_mass - point;
+		let distance_squared = offset.length_squared();
+		if self.children.is_empty() || self.size * self.size < theta * theta * distance_squared {
+			// The body itself is at zero distance and does not pull
+			let distance_squared = distance_squared + softening;
+			return offset * self.mass / (distance_squared * distance_squared.sqrt());
+		}
+		self.children.iter().fold(Vec2::ZERO, |sum, child| sum + child.acceleration(point, theta, softening))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn exact_with_zero_theta() {
+		// Deterministic scattered bodies, whole numbers keep the center of mass of a leaf exact
+		let mut seed = 7_u32;
+		let mut random = move || {
+			seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+			(seed >> 8) as f32 / (1 << 24) as f32
+		};
+		let bodies: Vec<_> = (0..40)
+			.map(|_| ((Vec2::new(random(), random()) * 200.0 - 100.0).round(), (1.0 + random() * 9.0).round()))
+			.collect();
+		let tree = QuadTree::new(&bodies).unwrap();
+		let softening = 4.0;
+
+		for &(point, _) in &bodies {
+			let pulls: Vec<_> = bodies.iter().map(|&(position, mass)| {
+				let offset = position - point;
+				let distance_squared = offset.length_squared() + softening;
+				offset * mass / (distance_squared * distance_squared.sqrt())
+			}).collect();
+			let direct = pulls.iter().fold(Vec2::ZERO, |sum, &pull| sum + pull);
+			// The pulls cancel each other, only the rounding of the sum order differs
+			let tolerance = pulls.iter().map(|pull| pull.length()).sum::<f32>() * 1e-5;
+			let approximated = tree.acceleration(point, 0.0, softening);
+			assert!(approximated.abs_diff_eq(direct, tolerance), "{approximated} != {direct}");
+		}
+	}
+}
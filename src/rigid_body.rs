@@ -1,3 +1,5 @@
+#![allow(clippy::forget_non_drop)]
+
 use bevy::prelude::*;
 use crate::prelude::*;
 
@@ -6,6 +8,7 @@ pub struct RigidBodyBundle {
 	#[bundle] pub kinematic: KinematicBundle,
 	#[bundle] pub collider: ColliderBundle,
 	pub body: Body,
+	pub material: PhysicsMaterial,
 	pub is_sleep: IsSleep,
 }
 
@@ -19,13 +22,25 @@ pub enum Body {
 #[derive(Default, Component, Deref, DerefMut)]
 pub struct IsSleep(bool);
 
+#[allow(clippy::type_complexity)]
 pub(crate) fn is_sleep(
-	mut query: Query<(&Velocity, &Acceleration, &Force, &mut IsSleep)>
+	mut query: Query<(
+		&Velocity,
+		&Acceleration,
+		&Force,
+		Option<&CollisionInfo>,
+		Option<&PassThrough>,
+		&mut IsSleep,
+	)>
 ) {
-	for (velocity, acceleration, force, mut is_sleep) in query.iter_mut() {
+	for (velocity, acceleration, force, info, pass, mut is_sleep) in query.iter_mut() {
+		// Only a body resting on something can sleep, otherwise it would never start to fall. A body
+		// dropping through a platform is resting on it until it wakes up
 		let sleep =
-			tend_zero_kinematic(velocity.linear, velocity.angular) ||
-			tend_zero_kinematic(acceleration.linear, acceleration.angular) ||
+			info.is_some_and(|info| info.is_colliding) &&
+			!pass.is_some_and(|pass| **pass > 0.0) &&
+			tend_zero_kinematic(velocity.linear, velocity.angular) &&
+			tend_zero_kinematic(acceleration.linear, acceleration.angular) &&
 			tend_zero_kinematic(force.linear, force.angular);
 		if **is_sleep != sleep { **is_sleep = sleep; }
 	}
@@ -33,6 +48,5 @@ pub(crate) fn is_sleep(
 
 #[inline]
 fn tend_zero_kinematic(l: Vec2, a: f32) -> bool {
-	l.x.abs() < f32::EPSILON || l.y.abs() < f32::EPSILON || a.abs() < f32::EPSILON
+	l.x.abs() < f32::EPSILON && l.y.abs() < f32::EPSILON && a.abs() < f32::EPSILON
 }
-
@@ -0,0 +1,338 @@
+use bevy::{prelude::*, math::Mat2, utils::{HashMap, HashSet}};
+use crate::{prelude::*, collision::Contact, joint};
+
+/// Penetration allowed between bodies, it avoids jitter in resting contacts
+const SLOP: f32 = 0.05;
+/// Fraction of the penetration or the joint error corrected every step
+pub(crate) const CORRECTION: f32 = 0.4;
+/// Depth a body can sink into a [`OneWay`] collider on top of the distance it moved in the step
+/// and still be blocked when they start to touch
+const ONE_WAY_TOLERANCE: f32 = 1.0;
+
+/// Surface properties of a rigid body used when it touches another one
+#[derive(Component, Clone, Copy)]
+pub struct PhysicsMaterial {
+	/// Bounciness, `0.0` does not bounce and `1.0` keeps all the speed
+	pub restitution: f32,
+	/// Coulomb friction coefficient
+	pub friction: f32,
+}
+
+impl Default for PhysicsMaterial {
+	fn default() -> Self {
+		Self {
+			restitution: 0.0,
+			friction: 0.5,
+		}
+	}
+}
+
+/// Speed in units/s of the surface of the collider going counterclockwise around its shape, the
+/// friction carries the bodies that touch it like a conveyor belt or an escalator while the
+/// collider itself does not move. The top of a box moves to the left with a positive speed
+#[derive(Default, Component, Clone, Copy, Deref, DerefMut)]
+pub struct SurfaceVelocity(pub f32);
+
+/// Collider that only blocks the bodies on the side its `normal` points to, the bodies that come
+/// from any other side go through it, like the platforms of a platformer that can be jumped onto
+/// from below. The normal is in the local space of the collider so it turns with it
+#[derive(Component, Clone, Copy)]
+pub struct OneWay {
+	pub normal: Vec2,
+}
+
+impl Default for OneWay {
+	fn default() -> Self {
+		Self { normal: Vec2::Y }
+	}
+}
+
+/// Seconds left for the body to go through every [`OneWay`] collider from any side, set it when
+/// the player presses down to drop from a platform. A body that starts to go through a collider
+/// keeps going through it until they stop touching
+#[derive(Default, Component, Clone, Copy, Deref, DerefMut)]
+pub struct PassThrough(pub f32);
+
+/// Times the joints are solved every step
+#[derive(Deref)]
+pub(crate) struct JointIterations(pub u32);
+
+/// Contacts between rigid bodies found in the current step
+#[derive(Default, Deref, DerefMut)]
+pub(crate) struct Contacts(Vec<(Entity, Entity, Contact)>);
+
+/// Pairs of a [`OneWay`] collider and a body that touch, with whether the body goes through it.
+/// The decision is kept while they touch so a body does not get stuck halfway
+#[derive(Default, Deref, DerefMut)]
+pub(crate) struct OneWayContacts(HashMap<(Entity, Entity), bool>);
+
+/// State of a rigid body copied out of the world while it is being solved
+#[derive(Clone, Copy)]
+pub(crate) struct SolverBody {
+	pub position: Vec2,
+	pub rotation: f32,
+	pub linear: Vec2,
+	pub angular: f32,
+	/// Per axis so locked axes can have an infinite mass
+	pub inv_mass: Vec2,
+	pub inv_inertia: f32,
+	pub material: PhysicsMaterial,
+	pub surface_speed: f32,
+}
+
+impl SolverBody {
+	/// Converts a point from the local space of the body to the world
+	pub fn world_point(&self, local: Vec2) -> Vec2 {
+		self.position + Mat2::from_angle(self.rotation) * local
+	}
+
+	pub fn velocity_at(&self, point: Vec2) -> Vec2 {
+		self.linear + self.angular * (point - self.position).perp()
+	}
+
+	pub fn apply_impulse(&mut self, impulse: Vec2, point: Vec2) {
+		self.linear  += impulse * self.inv_mass;
+		self.angular += (point - self.position).perp_dot(impulse) * self.inv_inertia;
+	}
+
+	/// Same as [`SolverBody::apply_impulse`] but changes the position and rotation directly, used
+	/// to correct the drift of the constraints
+	pub fn apply_displacement(&mut self, impulse: Vec2, point: Vec2) {
+		self.rotation += (point - self.position).perp_dot(impulse) * self.inv_inertia;
+		self.position += impulse * self.inv_mass;
+	}
+
+	fn is_static(&self) -> bool {
+		self.inv_mass == Vec2::ZERO && self.inv_inertia == 0.0
+	}
+}
+
+/// Inverse of the mass that resists an impulse along `axis` applied at the points `ra` and `rb`
+/// relative to each body, `0.0` if neither body can move along it
+pub(crate) fn effective_mass(a: &SolverBody, b: &SolverBody, ra: Vec2, rb: Vec2, axis: Vec2) -> f32 {
+	let ra_axis = ra.perp_dot(axis);
+	let rb_axis = rb.perp_dot(axis);
+	let inv_mass =
+		axis.dot(a.inv_mass * axis) + axis.dot(b.inv_mass * axis) +
+		ra_axis * ra_axis * a.inv_inertia + rb_axis * rb_axis * b.inv_inertia;
+	if inv_mass > 0.0 { inv_mass.recip() } else { 0.0 }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn detect_contacts(
+	time: Res<PhysicsTime>,
+	mut contacts: ResMut<Contacts>,
+	mut one_way_contacts: ResMut<OneWayContacts>,
+	joints: Query<&Joint>,
+	query: Query<(
+		Entity,
+		&Transform,
+		&ColliderShape,
+		&Body,
+		Option<&Velocity>,
+		Option<&OneWay>,
+		Option<&PassThrough>,
+	)>,
+) {
+	contacts.clear();
+	let mut touching = HashMap::default();
+	let connected: HashSet<_> = joints.iter()
+		.filter(|joint| !joint.collide_connected)
+		.flat_map(|joint| [(joint.entity_a, joint.entity_b), (joint.entity_b, joint.entity_a)])
+		.collect();
+	for [
+		(aid, atrans, ashape, abody, avelocity, aone_way, apass),
+		(bid, btrans, bshape, bbody, bvelocity, bone_way, bpass)
+	] in query.iter_combinations() {
+		if *abody == Body::Static && *bbody == Body::Static { continue; }
+		if connected.contains(&(aid, bid)) { continue; }
+		let Some(contact) = ashape.contact(GlobalTransform::from(*atrans), bshape, GlobalTransform::from(*btrans)) else { continue };
+
+		// The one-way collider and then the body that touches it
+		let one_way = match (aone_way, bone_way) {
+			(Some(one_way), _) => Some((one_way, (aid, atrans, ashape, avelocity), (bid, btrans, bshape, bvelocity, bpass))),
+			(None, Some(one_way)) => Some((one_way, (bid, btrans, bshape, bvelocity), (aid, atrans, ashape, avelocity, apass))),
+			(None, None) => None,
+		};
+		if let Some((one_way, (platform, ptrans, pshape, pvelocity), (body, trans, shape, velocity, pass))) = one_way {
+			let blocking = (ptrans.rotation * one_way.normal.extend(0.0)).truncate();
+			// Speed of the body toward the collider from the blocking side
+			let linear = |velocity: Option<&Velocity>| velocity.map_or(Vec2::ZERO, |v| v.linear);
+			let approach = (linear(pvelocity) - linear(velocity)).dot(blocking);
+			// How deep the lowest point of the body is under the top of the collider
+			let (_, top) = pshape.as_collider(GlobalTransform::from(*ptrans)).range_along_axis(blocking);
+			let (bottom, _) = shape.as_collider(GlobalTransform::from(*trans)).range_along_axis(blocking);
+			// Blocked only if it was over the collider at the start of the step and is not moving away
+			let from_outside = approach >= 0.0 && top - bottom <= approach * time.delta_seconds() + ONE_WAY_TOLERANCE;
+			let passes =
+				pass.is_some_and(|pass| **pass > 0.0) ||
+				one_way_contacts.get(&(platform, body)).copied().unwrap_or(!from_outside);
+			touching.insert((platform, body), passes);
+			if passes { continue; }
+		}
+		contacts.push((aid, bid, contact));
+	}
+	**one_way_contacts = touching;
+}
+
+pub(crate) fn pass_through(time: Res<PhysicsTime>, mut query: Query<&mut PassThrough>) {
+	for mut pass in query.iter_mut() {
+		if **pass > 0.0 { **pass = (**pass - time.delta_seconds()).max(0.0); }
+	}
+}
+
+/// Solves the joints and then the contacts so the bodies never end inside each other
+#[allow(clippy::type_complexity)]
+pub(crate) fn resolve(
+	mut commands: Commands,
+	mut broken_joints: EventWriter<JointBroken>,
+	time: Res<PhysicsTime>,
+	iterations: Res<JointIterations>,
+	contacts: Res<Contacts>,
+	mut joints: Query<(Entity, &mut Joint, Option<&BreakForce>, Option<&BreakTorque>)>,
+	mut query: Query<(
+		&mut Transform,
+		Option<&mut Velocity>,
+		Option<&Resistance>,
+		Option<&Body>,
+		Option<&PhysicsMaterial>,
+		Option<&LockedAxes>,
+		Option<&SurfaceVelocity>,
+	)>,
+) {
+	if contacts.is_empty() && joints.is_empty() { return; }
+
+	let ids = contacts.iter()
+		.flat_map(|&(aid, bid, _)| [aid, bid])
+		.chain(joints.iter().flat_map(|(_, joint, ..)| [joint.entity_a, joint.entity_b]));
+	let mut bodies = HashMap::default();
+	for id in ids {
+		if bodies.contains_key(&id) { continue; }
+		if let Ok((transform, velocity, resistance, body, material, locked, surface)) = query.get(id) {
+			// Entities without velocity can not be moved by the solver
+			let dynamic = body != Some(&Body::Static) && velocity.is_some();
+			let locked = locked.copied().unwrap_or_default();
+			let resistance = resistance.copied().unwrap_or_default();
+			let (linear, angular) = match velocity {
+				Some(velocity) if dynamic => (velocity.linear, velocity.angular),
+				_ => (Vec2::ZERO, 0.0),
+			};
+			bodies.insert(id, SolverBody {
+				position: transform.translation.truncate(),
+				rotation: transform.rotation.to_scaled_axis().z,
+				linear,
+				angular,
+				inv_mass:    if dynamic { locked.linear_mask()  * resistance.mass.recip()    } else { Vec2::ZERO },
+				inv_inertia: if dynamic { locked.angular_mask() * resistance.inertia.recip() } else { 0.0 },
+				material: material.copied().unwrap_or_default(),
+				surface_speed: surface.map_or(0.0, |s| **s),
+			});
+		}
+	}
+
+	// The joints keep the relative rotation the entities had when they were connected
+	for (_, mut joint, ..) in joints.iter_mut() {
+		if joint.reference_angle.is_some() { continue; }
+		if let (Some(a), Some(b)) = (bodies.get(&joint.entity_a), bodies.get(&joint.entity_b)) {
+			joint.reference_angle = Some(b.rotation - a.rotation);
+		}
+	}
+
+	let delta = time.delta_seconds();
+	// Impulses applied by the breakable joints during this step
+	let mut reactions = HashMap::<Entity, (Vec2, f32)>::default();
+	for iteration in 0..(*iterations).max(1) {
+		for (id, joint, break_force, break_torque) in joints.iter() {
+			let (Some(&a), Some(&b)) = (bodies.get(&joint.entity_a), bodies.get(&joint.entity_b)) else { continue };
+			let (solved_a, solved_b) = if iteration == 0 { joint.apply_forces(a, b, delta) } else { (a, b) };
+			let (solved_a, solved_b) = joint.solve(solved_a, solved_b);
+			bodies.insert(joint.entity_a, solved_a);
+			bodies.insert(joint.entity_b, solved_b);
+
+			// Without elapsed time the impulses can not be turned into forces, on the first frame for example
+			if break_force.is_none() && break_torque.is_none() || delta <= 0.0 { continue; }
+			let reaction = joint::reaction(&b, &solved_b, b.world_point(joint.anchor_b))
+				.or_else(|| joint::reaction(&a, &solved_a, a.world_point(joint.anchor_a)));
+			if let Some((impulse, angular_impulse)) = reaction {
+				let total = reactions.entry(id).or_default();
+				total.0 += impulse;
+				total.1 += angular_impulse;
+			}
+		}
+	}
+	for (id, (impulse, angular_impulse)) in reactions {
+		let Ok((_, joint, break_force, break_torque)) = joints.get(id) else { continue };
+		let (force, torque) = (impulse.length() / delta, angular_impulse.abs() / delta);
+		if break_force.is_some_and(|b| force > **b) || break_torque.is_some_and(|b| torque > **b) {
+			commands.entity(id).remove::<Joint>().remove::<BreakForce>().remove::<BreakTorque>();
+			broken_joints.send(JointBroken {
+				joint: id,
+				entity_a: joint.entity_a,
+				entity_b: joint.entity_b,
+			});
+		}
+	}
+
+	for (aid, bid, contact) in contacts.iter() {
+		if let (Some(&a), Some(&b)) = (bodies.get(aid), bodies.get(bid)) {
+			let (a, b) = solve_contact(a, b, contact);
+			bodies.insert(*aid, a);
+			bodies.insert(*bid, b);
+		}
+	}
+
+	for (id, body) in bodies {
+		if body.is_static() { continue; }
+		if let Ok((mut transform, velocity, ..)) = query.get_mut(id) {
+			transform.translation = body.position.extend(transform.translation.z);
+			if body.inv_inertia != 0.0 {
+				transform.rotation = Quat::from_rotation_z(body.rotation);
+			}
+			if let Some(mut velocity) = velocity {
+				velocity.linear  = body.linear;
+				velocity.angular = body.angular;
+			}
+		}
+	}
+}
+
+fn solve_contact(mut a: SolverBody, mut b: SolverBody, contact: &Contact) -> (SolverBody, SolverBody) {
+	let &Contact { normal, depth, point } = contact;
+
+	let ra = point - a.position;
+	let rb = point - b.position;
+
+	// Normal impulse, only when the bodies are getting closer
+	let relative = b.velocity_at(point) - a.velocity_at(point);
+	let normal_speed = relative.dot(normal);
+	if normal_speed < 0.0 {
+		let restitution = a.material.restitution.max(b.material.restitution);
+		let normal_impulse = -(1.0 + restitution) * normal_speed * effective_mass(&a, &b, ra, rb, normal);
+		a.apply_impulse(-normal * normal_impulse, point);
+		b.apply_impulse( normal * normal_impulse, point);
+
+		// Friction impulse, limited by the normal impulse. The moving surfaces go counterclockwise
+		// around each body and the normal points out of `a` and into `b`
+		let surface = normal.perp() * (a.surface_speed + b.surface_speed);
+		let relative = b.velocity_at(point) - a.velocity_at(point) - surface;
+		let tangent = (relative - normal * relative.dot(normal)).normalize_or_zero();
+		if tangent != Vec2::ZERO {
+			let friction = (a.material.friction * b.material.friction).sqrt();
+			let max_impulse = friction * normal_impulse;
+			let tangent_impulse = (-relative.dot(tangent) * effective_mass(&a, &b, ra, rb, tangent))
+				.clamp(-max_impulse, max_impulse);
+			a.apply_impulse(-tangent * tangent_impulse, point);
+			b.apply_impulse( tangent * tangent_impulse, point);
+		}
+	}
+
+	// Push the bodies apart
+	let inv_mass = normal.dot(a.inv_mass * normal) + normal.dot(b.inv_mass * normal);
+	if inv_mass > 0.0 {
+		let correction = normal * (depth - SLOP).max(0.0) * CORRECTION / inv_mass;
+		a.position -= correction * a.inv_mass;
+		b.position += correction * b.inv_mass;
+	}
+
+	(a, b)
+}
@@ -0,0 +1,160 @@
+use bevy::{prelude::*, ecs::system::SystemParam};
+use crate::prelude::*;
+
+/// Body simulated by [`TrajectoryPredictor`], it does not need to exist so it can be used to aim
+/// before throwing
+#[derive(Clone)]
+pub struct Projectile {
+	pub position: Vec2,
+	pub velocity: Vec2,
+	pub mass: f32,
+	pub drag: Drag,
+	pub damping: Damping,
+	pub limits: Option<VelocityLimits>,
+	pub locked: LockedAxes,
+	pub gravity_scale: f32,
+	/// Integrator of the body, [`None`] uses the one of [`PhysicsPlugin`]
+	pub integrator: Option<Integrator>,
+	/// Shape cast to find the first hit, [`None`] casts a ray
+	pub collider: Option<ColliderShape>,
+}
+
+impl Projectile {
+	pub fn new(position: Vec2, velocity: Vec2) -> Self {
+		Self {
+			position,
+			velocity,
+			mass: 1.0,
+			drag: default(),
+			damping: default(),
+			limits: None,
+			locked: default(),
+			gravity_scale: 1.0,
+			integrator: None,
+			collider: None,
+		}
+	}
+
+	pub fn with_mass(mut self, mass: f32) -> Self {
+		self.mass = mass;
+		self
+	}
+
+	pub fn with_drag(mut self, drag: Drag) -> Self {
+		self.drag = drag;
+		self
+	}
+
+	pub fn with_damping(mut self, damping: Damping) -> Self {
+		self.damping = damping;
+		self
+	}
+
+	pub fn with_limits(mut self, limits: VelocityLimits) -> Self {
+		self.limits = Some(limits);
+		self
+	}
+
+	pub fn with_locked_axes(mut self, locked: LockedAxes) -> Self {
+		self.locked = locked;
+		self
+	}
+
+	pub fn with_gravity_scale(mut self, gravity_scale: f32) -> Self {
+		self.gravity_scale = gravity_scale;
+		self
+	}
+
+	pub fn with_integrator(mut self, integrator: Integrator) -> Self {
+		self.integrator = Some(integrator);
+		self
+	}
+
+	pub fn with_collider(mut self, collider: ColliderShape) -> Self {
+		self.collider = Some(collider);
+		self
+	}
+}
+
+/// Path sampled by [`TrajectoryPredictor::predict`]
+pub struct Trajectory {
+	/// Position at the start and after every step, the last one is the position of the hit
+	pub points: Vec<Vec2>,
+	pub hit: Option<CastHit>,
+}
+
+/// Simulates a body forward with the same [`Gravity`], [`Drag`], [`Damping`], [`VelocityLimits`],
+/// [`LockedAxes`], [`Attractor`], [`ForceZone`] and integrator as the real ones, the zones are the
+/// ones that contain the position of the projectile. Use it as a system parameter
+#[derive(SystemParam)]
+pub struct TrajectoryPredictor<'w, 's> {
+	time: Res<'w, Time>,
+	integrator: Res<'w, Integrator>,
+	gravity_effect: Res<'w, GravityEffect>,
+	gravity: Option<Res<'w, Gravity>>,
+	attractors: Query<'w, 's, (&'static Transform, &'static Attractor)>,
+	zones: Query<'w, 's, (&'static Transform, &'static ForceZone)>,
+	spatial: SpatialQuery<'w, 's>,
+}
+
+impl<'w, 's> TrajectoryPredictor<'w, 's> {
+	/// Samples `steps` steps of `delta` seconds, if `filter` is [`Some`] it stops at the first
+	/// rigid body accepted by it
+	pub fn predict(
+		&self,
+		projectile: &Projectile,
+		steps: usize,
+		delta: f32,
+		filter: Option<&dyn Fn(Entity) -> bool>,
+	) -> Trajectory {
+		let integrator = projectile.integrator.unwrap_or(*self.integrator);
+		let g = self.gravity.as_deref().map_or(Vec2::ZERO, |g| **g * projectile.gravity_scale);
+		let seconds = self.time.seconds_since_startup() as f32;
+		let mask = projectile.locked.linear_mask();
+		let (mut position, mut velocity) = (projectile.position, projectile.velocity);
+		let mut points = Vec::with_capacity(steps + 1);
+		points.push(position);
+
+		for step in 0..steps {
+			// Same order as `update_transform`
+			velocity *= (-projectile.damping.linear * delta).exp();
+			// The zones push with the same acceleration during the whole step like the real ones
+			let pushed = self.spatial.point(position).into_iter()
+				.filter_map(|entity| self.zones.get(entity).ok())
+				.fold(Vec2::ZERO, |sum, (transform, zone)| {
+					sum + zone.acceleration(transform.translation.truncate(), position, velocity, seconds + step as f32 * delta)
+				});
+			// The attractors are evaluated at every predicted position
+			let acceleration = |displacement: Vec2, linear: Vec2| {
+				let point = position + displacement;
+				self.attractors.iter()
+					.map(|(transform, attractor)| attractor.acceleration(transform.translation.truncate(), point))
+					.fold(projectile.drag.linear_force(linear) / projectile.mass + pushed, |sum, acceleration| sum + acceleration)
+			};
+			let (mut displacement, linear) = integrator.step(velocity, delta, acceleration);
+			velocity = linear;
+			displacement += self.gravity_effect.apply(g, &mut velocity, delta);
+			if let Some(limits) = projectile.limits {
+				velocity = limits.clamp(velocity, 0.0).0;
+				if delta > 0.0 { displacement = limits.clamp(displacement / delta, 0.0).0 * delta; }
+			}
+			velocity *= mask;
+			displacement *= mask;
+
+			let next = position + displacement;
+			if let Some(filter) = filter {
+				let hit = match &projectile.collider {
+					Some(collider) => self.spatial.shape_cast(collider, position, next, filter),
+					None => self.spatial.raycast(position, next, filter),
+				};
+				if let Some(hit) = hit {
+					points.push(hit.point);
+					return Trajectory { points, hit: Some(hit) };
+				}
+			}
+			position = next;
+			points.push(position);
+		}
+		Trajectory { points, hit: None }
+	}
+}